@@ -26,11 +26,29 @@ fi
 buildargs=(
     ""
     "--target wasm32-unknown-unknown"
+    "--no-default-features"
+    "--no-default-features --target wasm32-unknown-unknown"
+    "--no-default-features --features tracing"
+    "--no-default-features --features testing"
     "--features tracing"
     "--features tracing --target wasm32-unknown-unknown"
+    "--features async"
+    "--features stats"
+    "--features thread-tracking"
+    "--features timestamps"
+    "--features testing"
 )
 
-for arg in "${buildargs[@]}"; do
+# Features whose dependencies don't support the MSRV
+stableargs=(
+    "--features epoch"
+    "--features gc"
+    "--features parking_lot"
+    "--features tokio"
+)
+
+check() {
+    local arg="$1"
     if [[ $version == "" ]]; then
         echo  "Checking '$arg' [default]"
     else
@@ -42,4 +60,14 @@ for arg in "${buildargs[@]}"; do
     fi
     cargo $version clippy $arg -- -D warnings
     echo
-done
\ No newline at end of file
+}
+
+for arg in "${buildargs[@]}"; do
+    check "$arg"
+done
+
+if [ "$is_msrv" == false ]; then
+    for arg in "${stableargs[@]}"; do
+        check "$arg"
+    done
+fi
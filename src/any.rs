@@ -0,0 +1,268 @@
+// Copyright (c) 2024 Yuki Kishimoto
+// Distributed under the MIT software license
+
+//! Type-erased atomic destructor
+
+#[cfg(feature = "std")]
+use alloc::boxed::Box;
+#[cfg(feature = "tracing")]
+use alloc::string::String;
+use alloc::sync::Arc;
+use core::fmt;
+
+#[cfg(feature = "async")]
+use crate::DestructorFuture;
+use crate::{AtomicDestroyer, AtomicDestructor, DestroyStats};
+
+/// Object-safe lifecycle of an [`AtomicDestructor`]
+///
+/// Allows handling destructors of different types together (i.e., `&dyn AnyAtomicDestructor`).
+pub trait AnyAtomicDestructor {
+    /// Get counter
+    fn counter(&self) -> usize;
+
+    /// Check if destroyed
+    fn is_destroyed(&self) -> bool;
+
+    /// Check if stealth
+    fn is_stealth(&self) -> bool;
+
+    /// Get a future that resolves when destroyed
+    #[cfg(feature = "async")]
+    fn wait_destroyed(&self) -> DestructorFuture;
+}
+
+impl<T> AnyAtomicDestructor for AtomicDestructor<T>
+where
+    T: AtomicDestroyer,
+{
+    fn counter(&self) -> usize {
+        AtomicDestructor::counter(self)
+    }
+
+    fn is_destroyed(&self) -> bool {
+        AtomicDestructor::is_destroyed(self)
+    }
+
+    fn is_stealth(&self) -> bool {
+        AtomicDestructor::is_stealth(self)
+    }
+
+    #[cfg(feature = "async")]
+    fn wait_destroyed(&self) -> DestructorFuture {
+        AtomicDestructor::wait_destroyed(self)
+    }
+}
+
+/// Object-safe part of [`AtomicDestroyer`]
+trait ErasedDestroyer: Send + Sync {
+    #[cfg(feature = "tracing")]
+    fn name(&self) -> Option<String>;
+
+    fn clone_allowed(&self) -> bool;
+
+    fn on_clone(&self, counter: usize);
+
+    fn on_any_drop(&self, remaining: usize);
+
+    fn on_destroy(&self);
+
+    fn on_destroy_with_stats(&self, stats: DestroyStats);
+
+    #[cfg(feature = "std")]
+    fn abort_on_destroy_panic(&self) -> bool;
+
+    #[cfg(feature = "std")]
+    fn on_destroy_fallible(
+        &self,
+        stats: DestroyStats,
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>>;
+
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result;
+}
+
+impl<T> ErasedDestroyer for T
+where
+    T: AtomicDestroyer + Send + Sync,
+{
+    #[cfg(feature = "tracing")]
+    fn name(&self) -> Option<String> {
+        AtomicDestroyer::name(self)
+    }
+
+    fn clone_allowed(&self) -> bool {
+        AtomicDestroyer::clone_allowed(self)
+    }
+
+    fn on_clone(&self, counter: usize) {
+        AtomicDestroyer::on_clone(self, counter)
+    }
+
+    fn on_any_drop(&self, remaining: usize) {
+        AtomicDestroyer::on_any_drop(self, remaining)
+    }
+
+    fn on_destroy(&self) {
+        AtomicDestroyer::on_destroy(self)
+    }
+
+    fn on_destroy_with_stats(&self, stats: DestroyStats) {
+        AtomicDestroyer::on_destroy_with_stats(self, stats)
+    }
+
+    #[cfg(feature = "std")]
+    fn abort_on_destroy_panic(&self) -> bool {
+        AtomicDestroyer::abort_on_destroy_panic(self)
+    }
+
+    #[cfg(feature = "std")]
+    fn on_destroy_fallible(
+        &self,
+        stats: DestroyStats,
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        AtomicDestroyer::on_destroy_fallible(self, stats)
+    }
+
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(self, f)
+    }
+}
+
+/// Type-erased destroyer
+///
+/// [`AtomicDestroyer`] requires [`Clone`], so it can't be used as `dyn AtomicDestroyer`.
+/// This wrapper allows storing destructors with different inners together (i.e., `Vec<AtomicDestructor<DynDestroyer>>`).
+///
+/// Trade-off: the inner is stored in an [`Arc`], so all the clones share the same inner instead of cloning it,
+/// and it can't be accessed anymore with its concrete type.
+#[derive(Clone)]
+pub struct DynDestroyer {
+    inner: Arc<dyn ErasedDestroyer>,
+}
+
+impl fmt::Debug for DynDestroyer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.inner.fmt(f)
+    }
+}
+
+impl AtomicDestroyer for DynDestroyer {
+    #[cfg(feature = "tracing")]
+    fn name(&self) -> Option<String> {
+        self.inner.name()
+    }
+
+    fn clone_allowed(&self) -> bool {
+        self.inner.clone_allowed()
+    }
+
+    fn on_clone(&self, counter: usize) {
+        self.inner.on_clone(counter);
+    }
+
+    fn on_any_drop(&self, remaining: usize) {
+        self.inner.on_any_drop(remaining);
+    }
+
+    fn on_destroy(&self) {
+        self.inner.on_destroy();
+    }
+
+    fn on_destroy_with_stats(&self, stats: DestroyStats) {
+        self.inner.on_destroy_with_stats(stats);
+    }
+
+    #[cfg(feature = "std")]
+    fn abort_on_destroy_panic(&self) -> bool {
+        self.inner.abort_on_destroy_panic()
+    }
+
+    #[cfg(feature = "std")]
+    fn on_destroy_fallible(
+        &self,
+        stats: DestroyStats,
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        self.inner.on_destroy_fallible(stats)
+    }
+}
+
+impl DynDestroyer {
+    /// Erase the inner type
+    pub fn new<T>(inner: T) -> Self
+    where
+        T: AtomicDestroyer + Send + Sync + 'static,
+    {
+        Self {
+            inner: Arc::new(inner),
+        }
+    }
+}
+
+impl AtomicDestructor<DynDestroyer> {
+    /// New wrapper with a type-erased inner
+    pub fn new_dyn<T>(inner: T) -> Self
+    where
+        T: AtomicDestroyer + Send + Sync + 'static,
+    {
+        Self::new(DynDestroyer::new(inner))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use core::sync::atomic::Ordering;
+
+    use super::*;
+    use crate::testing::CountingDestroyer;
+    use crate::StealthClone;
+
+    #[derive(Debug, Clone)]
+    struct InternalTestingA;
+
+    impl AtomicDestroyer for InternalTestingA {
+        fn on_destroy(&self) {}
+    }
+
+    #[derive(Debug, Clone)]
+    struct InternalTestingB;
+
+    impl AtomicDestroyer for InternalTestingB {
+        fn on_destroy(&self) {}
+    }
+
+    #[test]
+    fn test_any_atomic_destructor() {
+        let a = AtomicDestructor::new(InternalTestingA);
+        let b = AtomicDestructor::new(InternalTestingB);
+        let b_1 = b.clone();
+        let stealth = a.stealth_clone();
+
+        let destructors: [&dyn AnyAtomicDestructor; 3] = [&a, &b_1, &stealth];
+        let counters: Vec<usize> = destructors.iter().map(|d| d.counter()).collect();
+        assert_eq!(counters, vec![1, 2, 1]);
+        assert!(destructors[2].is_stealth());
+
+        drop(a);
+        assert!(AnyAtomicDestructor::is_destroyed(&stealth));
+        assert!(!AnyAtomicDestructor::is_destroyed(&b));
+    }
+
+    #[test]
+    fn test_dyn_destroyer() {
+        let inner = CountingDestroyer::new();
+        let destroyed = inner.destroyed_counter();
+
+        let mut destructors: Vec<AtomicDestructor<DynDestroyer>> = vec![
+            AtomicDestructor::new_dyn(InternalTestingA),
+            AtomicDestructor::new_dyn(inner.clone()),
+        ];
+        destructors.push(destructors[1].clone());
+        assert_eq!(format!("{:?}", *destructors[0]), "InternalTestingA");
+
+        destructors.pop();
+        assert_eq!(destroyed.load(Ordering::SeqCst), 0);
+
+        drop(destructors);
+        assert_eq!(destroyed.load(Ordering::SeqCst), 1);
+    }
+}
@@ -0,0 +1,121 @@
+// Copyright (c) 2024 Yuki Kishimoto
+// Distributed under the MIT software license
+
+//! Inner shared behind an `Arc`
+
+use alloc::sync::Arc;
+use core::ops::Deref;
+
+use crate::{AtomicDestroyer, AtomicDestructor, DestroyStats};
+
+/// Atomic destructor with the inner shared behind an `Arc`
+///
+/// Cloning the wrapper doesn't clone the inner, just increases the reference count of the `Arc`.
+pub type ArcInnerDestructor<T> = AtomicDestructor<ArcInner<T>>;
+
+/// Destroyer sharing the inner behind an `Arc`, for large inners that are expensive to clone
+///
+/// Created with [`AtomicDestructor::new_arc_inner`].
+#[derive(Debug)]
+pub struct ArcInner<T> {
+    inner: Arc<T>,
+}
+
+impl<T> Clone for ArcInner<T> {
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+        }
+    }
+}
+
+impl<T> Deref for ArcInner<T> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        &self.inner
+    }
+}
+
+impl<T> AtomicDestroyer for ArcInner<T>
+where
+    T: AtomicDestroyer,
+{
+    #[cfg(feature = "tracing")]
+    fn name(&self) -> Option<alloc::string::String> {
+        self.inner.name()
+    }
+
+    fn clone_allowed(&self) -> bool {
+        self.inner.clone_allowed()
+    }
+
+    fn on_clone(&self, counter: usize) {
+        self.inner.on_clone(counter);
+    }
+
+    fn on_any_drop(&self, remaining: usize) {
+        self.inner.on_any_drop(remaining);
+    }
+
+    #[cfg(feature = "std")]
+    fn abort_on_destroy_panic(&self) -> bool {
+        self.inner.abort_on_destroy_panic()
+    }
+
+    fn on_destroy(&self) {
+        self.inner.on_destroy();
+    }
+
+    fn on_destroy_with_stats(&self, stats: DestroyStats) {
+        self.inner.on_destroy_with_stats(stats);
+    }
+
+    #[cfg(feature = "std")]
+    fn on_destroy_fallible(
+        &self,
+        stats: DestroyStats,
+    ) -> Result<(), alloc::boxed::Box<dyn std::error::Error + Send + Sync>> {
+        self.inner.on_destroy_fallible(stats)
+    }
+}
+
+impl<T> ArcInnerDestructor<T>
+where
+    T: AtomicDestroyer,
+{
+    /// New wrapper with the inner shared by all the instances, instead of cloned
+    ///
+    /// See [`ArcInnerDestructor`].
+    pub fn new_arc_inner(inner: T) -> Self {
+        Self::new(ArcInner {
+            inner: Arc::new(inner),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use core::ptr;
+
+    use super::*;
+    use crate::testing::CountingDestroyer;
+    use crate::StealthClone;
+
+    #[test]
+    fn test_arc_inner() {
+        let t = AtomicDestructor::new_arc_inner(CountingDestroyer::new());
+        let t_1 = t.clone();
+        let stealth = t.stealth_clone();
+
+        // Not deep-cloned
+        let inner: &CountingDestroyer = &t;
+        assert!(ptr::eq(inner, &**t_1));
+        assert!(ptr::eq(inner, &**stealth));
+
+        drop(t);
+        drop(t_1);
+        assert!(stealth.is_destroyed());
+        assert_eq!(stealth.destroyed(), 1);
+    }
+}
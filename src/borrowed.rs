@@ -0,0 +1,111 @@
+// Copyright (c) 2024 Yuki Kishimoto
+// Distributed under the MIT software license
+
+//! Borrowed atomic destructor
+
+use core::fmt;
+use core::ops::Deref;
+
+use crate::{AtomicDestroyer, AtomicDestructor};
+
+/// Lifetime-scoped view of an [`AtomicDestructor`]
+///
+/// It borrows the destructor without touching the counter or cloning anything.
+pub struct BorrowedAtomicDestructor<'a, T>
+where
+    T: AtomicDestroyer,
+{
+    destructor: &'a AtomicDestructor<T>,
+}
+
+impl<'a, T> Clone for BorrowedAtomicDestructor<'a, T>
+where
+    T: AtomicDestroyer,
+{
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<'a, T> Copy for BorrowedAtomicDestructor<'a, T> where T: AtomicDestroyer {}
+
+impl<'a, T> fmt::Debug for BorrowedAtomicDestructor<'a, T>
+where
+    T: AtomicDestroyer,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("BorrowedAtomicDestructor")
+            .field(self.destructor)
+            .finish()
+    }
+}
+
+impl<'a, T> Deref for BorrowedAtomicDestructor<'a, T>
+where
+    T: AtomicDestroyer,
+{
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        self.destructor.inner()
+    }
+}
+
+impl<'a, T> From<&'a AtomicDestructor<T>> for BorrowedAtomicDestructor<'a, T>
+where
+    T: AtomicDestroyer,
+{
+    fn from(destructor: &'a AtomicDestructor<T>) -> Self {
+        Self { destructor }
+    }
+}
+
+impl<'a, T> BorrowedAtomicDestructor<'a, T>
+where
+    T: AtomicDestroyer,
+{
+    /// Get counter
+    pub fn counter(&self) -> usize {
+        self.destructor.counter()
+    }
+
+    /// Check if destroyed
+    pub fn is_destroyed(&self) -> bool {
+        self.destructor.is_destroyed()
+    }
+
+    /// Check if the borrowed destructor is stealth
+    pub fn is_stealth(&self) -> bool {
+        self.destructor.is_stealth()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::NoopDestroyer;
+    use crate::StealthClone;
+
+    fn read(b: BorrowedAtomicDestructor<'_, NoopDestroyer<u8>>) -> u8 {
+        b.0
+    }
+
+    #[test]
+    fn test_borrowed() {
+        let t = AtomicDestructor::new(NoopDestroyer(7));
+        let b = t.as_borrowed();
+        let b_1 = b; // Copy
+
+        assert_eq!(b.counter(), 1);
+        assert_eq!(b_1.counter(), 1);
+        assert!(!b.is_destroyed());
+        assert!(!b.is_stealth());
+        assert_eq!(read(b), 7);
+        assert_eq!(read(b_1), 7);
+        assert_eq!(t.counter(), 1);
+
+        let stealth = t.stealth_clone();
+        assert!(stealth.as_borrowed().is_stealth());
+        assert_eq!(t.counter(), 1);
+    }
+}
@@ -0,0 +1,129 @@
+// Copyright (c) 2024 Yuki Kishimoto
+// Distributed under the MIT software license
+
+//! Atomic destructor builder
+
+use alloc::sync::Arc;
+#[cfg(feature = "std")]
+use alloc::vec::Vec;
+
+#[cfg(feature = "std")]
+use crate::sync::Mutex;
+use crate::{AtomicDestroyer, AtomicDestructor, State};
+
+/// When the destruction is triggered
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum DestroyTrigger {
+    /// When the last non-stealth instance is dropped (default)
+    LastDrop,
+    /// When the first non-stealth instance is dropped
+    ///
+    /// The remaining instances are already destroyed, so dropping them doesn't do anything.
+    FirstDrop,
+}
+
+impl Default for DestroyTrigger {
+    fn default() -> Self {
+        Self::LastDrop
+    }
+}
+
+/// Atomic destructor builder
+#[derive(Debug, Clone)]
+pub struct AtomicDestructorBuilder<T>
+where
+    T: AtomicDestroyer,
+{
+    inner: T,
+    max_clones: Option<usize>,
+    trigger: DestroyTrigger,
+    forbid_stealth_during_destroy: bool,
+    #[cfg(feature = "std")]
+    listeners_capacity: usize,
+}
+
+impl<T> AtomicDestructorBuilder<T>
+where
+    T: AtomicDestroyer,
+{
+    /// New builder
+    pub fn new(inner: T) -> Self {
+        Self {
+            inner,
+            max_clones: None,
+            trigger: DestroyTrigger::default(),
+            forbid_stealth_during_destroy: false,
+            #[cfg(feature = "std")]
+            listeners_capacity: 0,
+        }
+    }
+
+    /// Max number of non-stealth instances that can be alive at the same time (default: unlimited)
+    ///
+    /// The built instance is included in the count.
+    /// When the limit is reached, [`AtomicDestructor::try_clone`] return `None` and `clone` panics.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `max` is `0`: the built instance would already exceed it.
+    #[track_caller]
+    pub fn max_clones(mut self, max: usize) -> Self {
+        assert!(max > 0, "max clones must be at least 1");
+        self.max_clones = Some(max);
+        self
+    }
+
+    /// When the destruction is triggered (default: [`DestroyTrigger::LastDrop`])
+    pub fn destroy_trigger(mut self, trigger: DestroyTrigger) -> Self {
+        self.trigger = trigger;
+        self
+    }
+
+    /// Report the destruction while stealth instances are alive (default: `false`)
+    ///
+    /// Stealth instances don't keep the lifecycle alive: using them after the destruction is a common bug.
+    /// When enabled, if any stealth instance is still alive after running the cleanup,
+    /// the destruction logs an error (with the `tracing` feature) and panics in debug builds.
+    /// The stealth instance running the destruction (i.e., a [`DestroyGuard`](crate::DestroyGuard)) isn't counted.
+    pub fn forbid_stealth_during_destroy(mut self, forbid: bool) -> Self {
+        self.forbid_stealth_during_destroy = forbid;
+        self
+    }
+
+    /// Pre-allocated capacity of the destruction listeners storage (default: `0`)
+    ///
+    /// For heavily observed resources: avoids the reallocations while subscribing.
+    /// Applies to both the pre and the post destruction listeners.
+    #[cfg(feature = "std")]
+    pub fn listeners_capacity(mut self, capacity: usize) -> Self {
+        self.listeners_capacity = capacity;
+        self
+    }
+
+    /// Build atomic destructor
+    pub fn build(self) -> AtomicDestructor<T> {
+        let mut state: State = State::new(1);
+        state.max_clones = self.max_clones;
+        state.trigger = self.trigger;
+        state.forbid_stealth_during_destroy = self.forbid_stealth_during_destroy;
+
+        #[cfg(feature = "std")]
+        {
+            state.pre_listeners = Mutex::new(Some(Vec::with_capacity(self.listeners_capacity)));
+            state.post_listeners = Mutex::new(Some(Vec::with_capacity(self.listeners_capacity)));
+        }
+
+        let state: Arc<State> = Arc::new(state);
+        AtomicDestructor {
+            #[cfg(feature = "thread-tracking")]
+            thread: state.track_thread(1),
+            state,
+            stealth: false,
+            label: None,
+            generation: 0,
+            #[cfg(feature = "std")]
+            joined: false,
+            inner: Some(self.inner),
+        }
+    }
+}
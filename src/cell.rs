@@ -0,0 +1,196 @@
+// Copyright (c) 2024 Yuki Kishimoto
+// Distributed under the MIT software license
+
+//! Shared mutable inner
+
+use alloc::boxed::Box;
+use alloc::sync::Arc;
+use core::mem;
+
+use crate::sync::{self, Mutex};
+use crate::{AtomicDestroyer, AtomicDestructor, DestroyStats};
+
+/// Destroyer with an inner shared and mutable by all the instances
+///
+/// Each instance usually owns a clone of the inner: here all the instances share the same one, behind a mutex.
+/// The callbacks are called on a copy of the current inner, taken out of the lock: they can access the cell without deadlocking,
+/// but every callback (i.e., [`AtomicDestroyer::on_clone`]) clones the inner.
+///
+/// Created with [`AtomicDestructor::new_cell`].
+#[derive(Debug, Clone)]
+pub struct AtomicDestructorCell<T> {
+    inner: Arc<Mutex<T>>,
+}
+
+impl<T> AtomicDestructorCell<T> {
+    /// Get a clone of the current inner
+    pub fn get(&self) -> T
+    where
+        T: Clone,
+    {
+        sync::lock(&self.inner).clone()
+    }
+
+    /// Replace the inner for all the instances, returning the old one
+    pub fn replace(&self, inner: T) -> T {
+        mem::replace(&mut *sync::lock(&self.inner), inner)
+    }
+}
+
+impl<T> AtomicDestroyer for AtomicDestructorCell<T>
+where
+    T: AtomicDestroyer,
+{
+    #[cfg(feature = "tracing")]
+    fn name(&self) -> Option<alloc::string::String> {
+        self.get().name()
+    }
+
+    fn clone_allowed(&self) -> bool {
+        self.get().clone_allowed()
+    }
+
+    fn on_clone(&self, counter: usize) {
+        self.get().on_clone(counter);
+    }
+
+    fn on_any_drop(&self, remaining: usize) {
+        self.get().on_any_drop(remaining);
+    }
+
+    fn abort_on_destroy_panic(&self) -> bool {
+        self.get().abort_on_destroy_panic()
+    }
+
+    fn on_destroy(&self) {
+        self.get().on_destroy();
+    }
+
+    fn on_destroy_with_stats(&self, stats: DestroyStats) {
+        self.get().on_destroy_with_stats(stats);
+    }
+
+    fn on_destroy_fallible(
+        &self,
+        stats: DestroyStats,
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        self.get().on_destroy_fallible(stats)
+    }
+}
+
+impl<T> AtomicDestructor<AtomicDestructorCell<T>>
+where
+    T: AtomicDestroyer,
+{
+    /// New wrapper with an inner shared and mutable by all the instances
+    ///
+    /// See [`AtomicDestructorCell`].
+    pub fn new_cell(inner: T) -> Self {
+        Self::new(AtomicDestructorCell {
+            inner: Arc::new(Mutex::new(inner)),
+        })
+    }
+
+    /// Replace the inner with `new_inner` only if it's equal to `expected`
+    ///
+    /// The comparison and the replacement are done under the same lock, and the [version](AtomicDestructor::version) is increased if replaced.
+    /// Return the old inner if replaced, otherwise give back `new_inner`.
+    pub fn compare_and_swap_inner(&self, expected: &T, new_inner: T) -> Result<T, T>
+    where
+        T: PartialEq,
+    {
+        let mut inner = sync::lock(&self.inner().inner);
+        if *inner == *expected {
+            self.bump_version();
+            Ok(mem::replace(&mut *inner, new_inner))
+        } else {
+            Err(new_inner)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec::Vec;
+    use std::thread;
+
+    use super::*;
+    use crate::NoopDestroyer;
+
+    #[test]
+    fn test_compare_and_swap_inner() {
+        let t = AtomicDestructor::new_cell(NoopDestroyer(1));
+        let t_1 = t.clone();
+
+        assert_eq!(
+            t.compare_and_swap_inner(&NoopDestroyer(2), NoopDestroyer(3)),
+            Err(NoopDestroyer(3))
+        );
+        assert_eq!(
+            t.compare_and_swap_inner(&NoopDestroyer(1), NoopDestroyer(3)),
+            Ok(NoopDestroyer(1))
+        );
+
+        // Shared by all the instances
+        assert_eq!(t_1.version(), 1);
+        assert_eq!(t_1.get(), NoopDestroyer(3));
+        assert_eq!(t_1.replace(NoopDestroyer(4)), NoopDestroyer(3));
+        assert_eq!(t.get(), NoopDestroyer(4));
+    }
+
+    #[test]
+    fn test_compare_and_swap_inner_racing() {
+        let t = AtomicDestructor::new_cell(NoopDestroyer(0usize));
+
+        let handles: Vec<_> = (0..8)
+            .map(|_| {
+                let t = t.clone();
+                thread::spawn(move || {
+                    let mut swaps: usize = 0;
+                    while swaps < 100 {
+                        let current = t.get();
+                        if t.compare_and_swap_inner(&current, NoopDestroyer(current.0 + 1))
+                            .is_ok()
+                        {
+                            swaps += 1;
+                        }
+                    }
+                })
+            })
+            .collect();
+
+        for handle in handles.into_iter() {
+            handle.join().unwrap();
+        }
+
+        // No lost update
+        assert_eq!(t.get(), NoopDestroyer(800));
+    }
+
+    #[derive(Debug, Clone, Default)]
+    struct InternalReentrant {
+        cell: Arc<Mutex<Option<AtomicDestructorCell<InternalReentrant>>>>,
+        destroyed: Arc<Mutex<bool>>,
+    }
+
+    impl AtomicDestroyer for InternalReentrant {
+        fn on_destroy(&self) {
+            // Access the cell from the callback
+            let cell = sync::lock(&self.cell).take();
+            if let Some(cell) = cell {
+                cell.replace(cell.get());
+                *sync::lock(&self.destroyed) = true;
+            }
+        }
+    }
+
+    #[test]
+    fn test_reentrant_callback() {
+        let inner = InternalReentrant::default();
+        let t = AtomicDestructor::new_cell(inner.clone());
+        *sync::lock(&inner.cell) = Some((*t).clone());
+
+        drop(t);
+        assert!(*sync::lock(&inner.destroyed));
+    }
+}
@@ -0,0 +1,157 @@
+// Copyright (c) 2024 Yuki Kishimoto
+// Distributed under the MIT software license
+
+//! Lifecycle checkpoints
+
+#[cfg(feature = "testing")]
+use core::sync::atomic::Ordering;
+
+use crate::{AtomicDestroyer, AtomicDestructor};
+
+/// Snapshot of a lifecycle, for test rollbacks (i.e., property-based testing)
+///
+/// Created with [`AtomicDestructor::checkpoint`].
+#[derive(Debug, Clone)]
+pub struct DestructorCheckpoint<T> {
+    counter: usize,
+    destroyed: bool,
+    inner: T,
+}
+
+impl<T> DestructorCheckpoint<T> {
+    /// Get the captured counter
+    pub fn counter(&self) -> usize {
+        self.counter
+    }
+
+    /// Check if the lifecycle was destroyed
+    pub fn is_destroyed(&self) -> bool {
+        self.destroyed
+    }
+
+    /// Get the captured inner
+    pub fn inner(&self) -> &T {
+        &self.inner
+    }
+}
+
+impl<T> AtomicDestructor<T>
+where
+    T: AtomicDestroyer,
+{
+    /// Capture the counter, the destroyed flag and a clone of the inner
+    pub fn checkpoint(&self) -> DestructorCheckpoint<T> {
+        let (counter, destroyed) = self.load_consistent();
+        DestructorCheckpoint {
+            counter,
+            destroyed,
+            inner: self.inner().clone(),
+        }
+    }
+
+    /// Restore a [checkpoint](AtomicDestructor::checkpoint)
+    ///
+    /// The counter and the destroyed flag are overwritten for **all** the instances, and the inner of `self` is replaced.
+    /// No callback is called. If `self` belongs to a previous generation, it's moved to the current one.
+    ///
+    /// Only for tests: the counter is no longer related to the alive instances.
+    #[cfg(feature = "testing")]
+    pub fn restore(&mut self, checkpoint: DestructorCheckpoint<T>) {
+        self.generation = self.state.generation.load(Ordering::SeqCst);
+        self.state
+            .counter
+            .store(checkpoint.counter, Ordering::SeqCst);
+        self.state.pending.store(false, Ordering::SeqCst);
+        self.state
+            .destroyed
+            .store(checkpoint.destroyed, Ordering::SeqCst);
+        self.state
+            .destroying
+            .store(checkpoint.destroyed, Ordering::SeqCst);
+        *self.inner_mut() = checkpoint.inner;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::sync::Arc;
+    use core::sync::atomic::{AtomicUsize, Ordering};
+
+    use super::*;
+    use crate::StealthClone;
+
+    #[derive(Debug, Clone)]
+    struct Internal {
+        value: usize,
+        destroyed: Arc<AtomicUsize>,
+    }
+
+    impl AtomicDestroyer for Internal {
+        fn on_destroy(&self) {
+            self.destroyed.fetch_add(1, Ordering::SeqCst);
+        }
+    }
+
+    #[test]
+    fn test_checkpoint() {
+        let destroyed = Arc::new(AtomicUsize::new(0));
+        let t = AtomicDestructor::new(Internal {
+            value: 1,
+            destroyed: destroyed.clone(),
+        });
+        let t_1 = t.clone();
+
+        let checkpoint = t.checkpoint();
+        assert_eq!(checkpoint.counter(), 2);
+        assert!(!checkpoint.is_destroyed());
+        assert_eq!(checkpoint.inner().value, 1);
+
+        drop(t_1);
+        let stealth = t.stealth_clone();
+        drop(t);
+        let checkpoint = stealth.checkpoint();
+        assert_eq!(checkpoint.counter(), 0);
+        assert!(checkpoint.is_destroyed());
+    }
+
+    #[test]
+    #[cfg(feature = "testing")]
+    fn test_restore() {
+        let destroyed = Arc::new(AtomicUsize::new(0));
+        let mut t = AtomicDestructor::new(Internal {
+            value: 1,
+            destroyed: destroyed.clone(),
+        });
+        let checkpoint = t.checkpoint();
+
+        t.value = 2;
+        assert!(t.try_destroy());
+        assert_eq!(destroyed.load(Ordering::SeqCst), 1);
+
+        t.restore(checkpoint.clone());
+
+        // Behaves like a fresh instance built from the checkpoint
+        let fresh = AtomicDestructor::new(checkpoint.inner().clone());
+        assert_eq!(t.counter(), fresh.counter());
+        assert_eq!(t.is_destroyed(), fresh.is_destroyed());
+        assert_eq!(t.value, fresh.value);
+        assert!(t.peek().is_some());
+
+        let t_1 = t.clone();
+        let fresh_1 = fresh.clone();
+        assert_eq!(t.counter(), fresh.counter());
+
+        drop(t_1);
+        drop(fresh_1);
+        assert!(!t.is_destroyed());
+        assert!(!fresh.is_destroyed());
+
+        let observer = t.stealth_clone();
+        let fresh_observer = fresh.stealth_clone();
+        drop(t);
+        drop(fresh);
+        assert!(observer.is_destroyed());
+        assert!(fresh_observer.is_destroyed());
+        assert_eq!(destroyed.load(Ordering::SeqCst), 3);
+    }
+}
@@ -0,0 +1,213 @@
+// Copyright (c) 2024 Yuki Kishimoto
+// Distributed under the MIT software license
+
+//! Async cleanups, executed by a runner of choice
+
+use alloc::boxed::Box;
+use alloc::collections::VecDeque;
+use alloc::sync::Arc;
+use core::fmt::{self, Debug};
+use core::future::Future;
+use core::mem;
+use core::ops::{Deref, DerefMut};
+use core::pin::Pin;
+
+use crate::sync::{self, Mutex};
+use crate::{AtomicDestroyer, AtomicDestructor};
+
+/// Boxed cleanup future
+pub type BoxFuture = Pin<Box<dyn Future<Output = ()> + Send + 'static>>;
+
+/// Async destroyer
+///
+/// Like [`AtomicDestroyer`], but the destruction is a future, queued in a [`CleanupQueue`] instead of being executed.
+pub trait AsyncDestroyer: Debug + Clone {
+    /// Build the cleanup future, called when all instances are dropped
+    fn on_destroy_async(&self) -> BoxFuture;
+}
+
+/// Queue of the pending cleanup futures
+///
+/// Decouples the async cleanups from a specific runtime: the futures are executed by
+/// the runner passed to [`CleanupQueue::drain_destructions`]. Cheap to clone.
+#[derive(Clone, Default)]
+pub struct CleanupQueue {
+    futures: Arc<Mutex<VecDeque<BoxFuture>>>,
+}
+
+impl Debug for CleanupQueue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CleanupQueue")
+            .field("pending", &self.len())
+            .finish()
+    }
+}
+
+impl CleanupQueue {
+    /// New empty queue
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Number of pending cleanups
+    pub fn len(&self) -> usize {
+        sync::lock(&self.futures).len()
+    }
+
+    /// Check if there are no pending cleanups
+    pub fn is_empty(&self) -> bool {
+        sync::lock(&self.futures).is_empty()
+    }
+
+    /// Pass all the pending cleanups to `runner`, in destruction order, and return how many were drained
+    ///
+    /// The runner is called without holding the lock: cleanups queued meanwhile are drained too.
+    pub fn drain_destructions<F>(&self, mut runner: F) -> usize
+    where
+        F: FnMut(BoxFuture),
+    {
+        let mut drained: usize = 0;
+        loop {
+            let futures: VecDeque<BoxFuture> = mem::take(&mut *sync::lock(&self.futures));
+            if futures.is_empty() {
+                return drained;
+            }
+
+            for future in futures.into_iter() {
+                runner(future);
+                drained += 1;
+            }
+        }
+    }
+
+    fn push(&self, future: BoxFuture) {
+        sync::lock(&self.futures).push_back(future);
+    }
+}
+
+/// Destroyer that queues the cleanup future of an [`AsyncDestroyer`]
+///
+/// Created with [`AtomicDestructor::with_async_cleanup`].
+#[derive(Debug, Clone)]
+pub struct AsyncCleanupDestroyer<T> {
+    inner: T,
+    queue: CleanupQueue,
+}
+
+impl<T> Deref for AsyncCleanupDestroyer<T> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        &self.inner
+    }
+}
+
+impl<T> DerefMut for AsyncCleanupDestroyer<T> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.inner
+    }
+}
+
+impl<T> AtomicDestroyer for AsyncCleanupDestroyer<T>
+where
+    T: AsyncDestroyer,
+{
+    fn on_destroy(&self) {
+        self.queue.push(self.inner.on_destroy_async());
+    }
+}
+
+impl<T> AtomicDestructor<AsyncCleanupDestroyer<T>>
+where
+    T: AsyncDestroyer,
+{
+    /// New wrapper that queues the cleanup future in `queue` when destroyed
+    ///
+    /// See [`CleanupQueue::drain_destructions`].
+    pub fn with_async_cleanup(inner: T, queue: &CleanupQueue) -> Self {
+        Self::new(AsyncCleanupDestroyer {
+            inner,
+            queue: queue.clone(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec::Vec;
+    use core::task::{Context, Waker};
+    use std::task::Wake;
+    use std::thread::{self, Thread};
+
+    use super::*;
+
+    #[derive(Debug, Clone)]
+    struct Internal {
+        id: usize,
+        events: Arc<Mutex<Vec<usize>>>,
+    }
+
+    impl AsyncDestroyer for Internal {
+        fn on_destroy_async(&self) -> BoxFuture {
+            let id: usize = self.id;
+            let events = self.events.clone();
+            Box::pin(async move {
+                sync::lock(&events).push(id);
+            })
+        }
+    }
+
+    struct ThreadWaker(Thread);
+
+    impl Wake for ThreadWaker {
+        fn wake(self: Arc<Self>) {
+            self.0.unpark();
+        }
+    }
+
+    fn block_on(mut future: BoxFuture) {
+        let waker = Waker::from(Arc::new(ThreadWaker(thread::current())));
+        let mut cx = Context::from_waker(&waker);
+        while future.as_mut().poll(&mut cx).is_pending() {
+            thread::park();
+        }
+    }
+
+    #[test]
+    fn test_drain_destructions() {
+        let queue = CleanupQueue::new();
+        let events = Arc::new(Mutex::new(Vec::new()));
+
+        let a = AtomicDestructor::with_async_cleanup(
+            Internal {
+                id: 1,
+                events: events.clone(),
+            },
+            &queue,
+        );
+        let b = AtomicDestructor::with_async_cleanup(
+            Internal {
+                id: 2,
+                events: events.clone(),
+            },
+            &queue,
+        );
+        let a_1 = a.clone();
+
+        drop(a);
+        assert!(queue.is_empty());
+
+        drop(b);
+        drop(a_1);
+        assert_eq!(queue.len(), 2);
+
+        // Queued, not executed
+        assert!(sync::lock(&events).is_empty());
+
+        assert_eq!(queue.drain_destructions(block_on), 2);
+        assert!(queue.is_empty());
+        assert_eq!(*sync::lock(&events), [2, 1]);
+
+        assert_eq!(queue.drain_destructions(block_on), 0);
+    }
+}
@@ -0,0 +1,221 @@
+// Copyright (c) 2024 Yuki Kishimoto
+// Distributed under the MIT software license
+
+//! Closure-based destroyer
+
+use alloc::sync::Arc;
+use core::fmt::{self, Debug};
+use core::ops::{Deref, DerefMut};
+
+use crate::{AtomicDestroyer, AtomicDestructor};
+
+/// Destroyer that calls a closure on destruction
+///
+/// Created with [`AtomicDestructor::from_closure`].
+pub struct ClosureDestroyer<T> {
+    inner: T,
+    on_destroy: Arc<dyn Fn(&T) + Send + Sync>,
+}
+
+impl<T> Clone for ClosureDestroyer<T>
+where
+    T: Clone,
+{
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+            on_destroy: self.on_destroy.clone(),
+        }
+    }
+}
+
+impl<T> Debug for ClosureDestroyer<T>
+where
+    T: Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ClosureDestroyer")
+            .field("inner", &self.inner)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<T> Deref for ClosureDestroyer<T> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        &self.inner
+    }
+}
+
+impl<T> DerefMut for ClosureDestroyer<T> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.inner
+    }
+}
+
+impl<T> AtomicDestroyer for ClosureDestroyer<T>
+where
+    T: Debug + Clone,
+{
+    fn on_destroy(&self) {
+        (self.on_destroy)(&self.inner);
+    }
+}
+
+impl<T> AtomicDestructor<ClosureDestroyer<T>>
+where
+    T: Debug + Clone + Send + Sync,
+{
+    /// New wrapper that calls `on_destroy` when destroyed, without implementing [`AtomicDestroyer`]
+    pub fn from_closure<F>(inner: T, on_destroy: F) -> Self
+    where
+        F: Fn(&T) + Send + Sync + 'static,
+    {
+        Self::new(ClosureDestroyer {
+            inner,
+            on_destroy: Arc::new(on_destroy),
+        })
+    }
+}
+
+/// Callback executed on every clone, with the new counter value
+type OnClone<T> = Arc<dyn Fn(&T, usize) + Send + Sync>;
+
+/// Destroyer that calls a closure on every clone and another one on destruction
+///
+/// Created with [`AtomicDestructor::from_closures`].
+pub struct FullClosureDestroyer<T> {
+    inner: T,
+    on_destroy: Arc<dyn Fn(&T) + Send + Sync>,
+    on_clone: OnClone<T>,
+}
+
+impl<T> Clone for FullClosureDestroyer<T>
+where
+    T: Clone,
+{
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+            on_destroy: self.on_destroy.clone(),
+            on_clone: self.on_clone.clone(),
+        }
+    }
+}
+
+impl<T> Debug for FullClosureDestroyer<T>
+where
+    T: Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("FullClosureDestroyer")
+            .field("inner", &self.inner)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<T> Deref for FullClosureDestroyer<T> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        &self.inner
+    }
+}
+
+impl<T> DerefMut for FullClosureDestroyer<T> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.inner
+    }
+}
+
+impl<T> AtomicDestroyer for FullClosureDestroyer<T>
+where
+    T: Debug + Clone,
+{
+    fn on_clone(&self, counter: usize) {
+        (self.on_clone)(&self.inner, counter);
+    }
+
+    fn on_destroy(&self) {
+        (self.on_destroy)(&self.inner);
+    }
+}
+
+impl<T> AtomicDestructor<FullClosureDestroyer<T>>
+where
+    T: Debug + Clone + Send + Sync,
+{
+    /// New wrapper that calls `on_clone` when cloned and `on_destroy` when destroyed, without implementing [`AtomicDestroyer`]
+    ///
+    /// `on_clone` receives the new counter value.
+    pub fn from_closures<D, C>(inner: T, on_destroy: D, on_clone: C) -> Self
+    where
+        D: Fn(&T) + Send + Sync + 'static,
+        C: Fn(&T, usize) + Send + Sync + 'static,
+    {
+        Self::new(FullClosureDestroyer {
+            inner,
+            on_destroy: Arc::new(on_destroy),
+            on_clone: Arc::new(on_clone),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec::Vec;
+    use core::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Mutex;
+
+    use super::*;
+    use crate::StealthClone;
+
+    #[test]
+    fn test_from_closure() {
+        let destroyed = Arc::new(AtomicUsize::new(0));
+
+        let d = destroyed.clone();
+        let t = AtomicDestructor::from_closure(5usize, move |value| {
+            d.fetch_add(*value, Ordering::SeqCst);
+        });
+        let t_1 = t.clone();
+        assert_eq!(**t_1, 5);
+
+        drop(t);
+        assert_eq!(destroyed.load(Ordering::SeqCst), 0);
+
+        drop(t_1);
+        assert_eq!(destroyed.load(Ordering::SeqCst), 5);
+    }
+
+    #[test]
+    fn test_from_closures() {
+        let destroyed = Arc::new(AtomicUsize::new(0));
+        let counters = Arc::new(Mutex::new(Vec::new()));
+
+        let d = destroyed.clone();
+        let c = counters.clone();
+        let t = AtomicDestructor::from_closures(
+            "value",
+            move |_| {
+                d.fetch_add(1, Ordering::SeqCst);
+            },
+            move |_, counter| c.lock().unwrap().push(counter),
+        );
+        assert!(counters.lock().unwrap().is_empty());
+
+        let t_1 = t.clone();
+        let clones = t.clone_n(2);
+        let _stealth = t.stealth_clone();
+        assert_eq!(**t_1, "value");
+        assert_eq!(*counters.lock().unwrap(), vec![2, 3, 4]);
+
+        drop(t);
+        drop(t_1);
+        assert_eq!(destroyed.load(Ordering::SeqCst), 0);
+
+        drop(clones);
+        assert_eq!(destroyed.load(Ordering::SeqCst), 1);
+    }
+}
@@ -0,0 +1,262 @@
+// Copyright (c) 2024 Yuki Kishimoto
+// Distributed under the MIT software license
+
+//! Destruction with external context
+
+#[cfg(feature = "std")]
+use alloc::boxed::Box;
+#[cfg(feature = "tracing")]
+use alloc::string::String;
+use alloc::sync::Arc;
+use core::fmt;
+use core::ops::{Deref, DerefMut};
+
+use crate::{AtomicDestroyer, AtomicDestructor, DestroyStats};
+
+/// Atomic destructor with a shared destroy context
+///
+/// See [`WithDestroyContext`].
+pub type ContextualDestructor<T, C> = AtomicDestructor<WithDestroyContext<T, C>>;
+
+/// Destroyer that receives an external context
+pub trait ContextDestroyer<C>: AtomicDestroyer {
+    /// Destroy with context and the [final statistics](DestroyStats) of the lifecycle
+    ///
+    /// By default, call [`AtomicDestroyer::on_destroy_with_stats`] and ignore the context.
+    fn on_destroy_ctx(&self, context: &C, stats: DestroyStats) {
+        let _ = context;
+        self.on_destroy_with_stats(stats);
+    }
+
+    /// Fallible version of [`ContextDestroyer::on_destroy_ctx`]
+    ///
+    /// This is the one called by the destructor: by default, it calls [`ContextDestroyer::on_destroy_ctx`] and returns `Ok(())`.
+    /// The context replaces the regular hooks: [`AtomicDestroyer::on_destroy_fallible`] of the inner isn't called.
+    #[cfg(feature = "std")]
+    fn on_destroy_ctx_fallible(
+        &self,
+        context: &C,
+        stats: DestroyStats,
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        self.on_destroy_ctx(context, stats);
+        Ok(())
+    }
+}
+
+/// Destroyer that calls [`ContextDestroyer::on_destroy_ctx`] with a shared context
+///
+/// The context (i.e., loggers, pools, runtimes) lives outside the inner and is shared by all the clones.
+///
+/// # Example
+///
+/// ```rust
+/// use std::sync::{Arc, Mutex};
+///
+/// use atomic_destructor::{AtomicDestroyer, AtomicDestructor, ContextDestroyer, DestroyStats};
+///
+/// #[derive(Default)]
+/// struct Pool {
+///     released: Mutex<Vec<u32>>,
+/// }
+///
+/// #[derive(Debug, Clone)]
+/// struct Connection {
+///     id: u32,
+/// }
+///
+/// impl AtomicDestroyer for Connection {
+///     fn on_destroy(&self) {}
+/// }
+///
+/// impl ContextDestroyer<Arc<Pool>> for Connection {
+///     fn on_destroy_ctx(&self, pool: &Arc<Pool>, _stats: DestroyStats) {
+///         // Give the connection back to the pool
+///         pool.released.lock().unwrap().push(self.id);
+///     }
+/// }
+///
+/// let pool = Arc::new(Pool::default());
+///
+/// let conn = AtomicDestructor::with_destroy_context(Connection { id: 1 }, pool.clone());
+/// let conn_1 = conn.clone();
+///
+/// drop(conn);
+/// drop(conn_1);
+///
+/// assert_eq!(*pool.released.lock().unwrap(), vec![1]);
+/// ```
+pub struct WithDestroyContext<T, C>
+where
+    T: ContextDestroyer<C>,
+{
+    inner: T,
+    context: Arc<C>,
+}
+
+impl<T, C> Clone for WithDestroyContext<T, C>
+where
+    T: ContextDestroyer<C>,
+{
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+            context: self.context.clone(),
+        }
+    }
+}
+
+impl<T, C> fmt::Debug for WithDestroyContext<T, C>
+where
+    T: ContextDestroyer<C>,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("WithDestroyContext")
+            .field("inner", &self.inner)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<T, C> Deref for WithDestroyContext<T, C>
+where
+    T: ContextDestroyer<C>,
+{
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        &self.inner
+    }
+}
+
+impl<T, C> DerefMut for WithDestroyContext<T, C>
+where
+    T: ContextDestroyer<C>,
+{
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.inner
+    }
+}
+
+impl<T, C> AtomicDestroyer for WithDestroyContext<T, C>
+where
+    T: ContextDestroyer<C>,
+{
+    #[cfg(feature = "tracing")]
+    fn name(&self) -> Option<String> {
+        self.inner.name()
+    }
+
+    fn clone_allowed(&self) -> bool {
+        self.inner.clone_allowed()
+    }
+
+    fn on_clone(&self, counter: usize) {
+        self.inner.on_clone(counter);
+    }
+
+    fn on_any_drop(&self, remaining: usize) {
+        self.inner.on_any_drop(remaining);
+    }
+
+    #[cfg(feature = "std")]
+    fn abort_on_destroy_panic(&self) -> bool {
+        self.inner.abort_on_destroy_panic()
+    }
+
+    fn on_destroy(&self) {
+        self.on_destroy_with_stats(DestroyStats::default());
+    }
+
+    fn on_destroy_with_stats(&self, stats: DestroyStats) {
+        self.inner.on_destroy_ctx(&self.context, stats);
+    }
+
+    #[cfg(feature = "std")]
+    fn on_destroy_fallible(
+        &self,
+        stats: DestroyStats,
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        self.inner.on_destroy_ctx_fallible(&self.context, stats)
+    }
+}
+
+impl<T, C> WithDestroyContext<T, C>
+where
+    T: ContextDestroyer<C>,
+{
+    /// Wrap inner
+    pub fn new(inner: T, context: C) -> Self {
+        Self {
+            inner,
+            context: Arc::new(context),
+        }
+    }
+
+    /// Get context
+    pub fn context(&self) -> &C {
+        &self.context
+    }
+}
+
+impl<T, C> AtomicDestructor<WithDestroyContext<T, C>>
+where
+    T: ContextDestroyer<C>,
+{
+    /// New wrapper with a context passed to [`ContextDestroyer::on_destroy_ctx`]
+    pub fn with_destroy_context(inner: T, context: C) -> Self {
+        Self::new(WithDestroyContext::new(inner, context))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use core::sync::atomic::{AtomicUsize, Ordering};
+
+    use super::*;
+    use crate::testing::CountingDestroyer;
+
+    impl ContextDestroyer<()> for CountingDestroyer {}
+
+    #[test]
+    fn test_default_on_destroy_ctx() {
+        let inner = CountingDestroyer::new();
+        let destroyed = inner.destroyed_counter();
+        let t = AtomicDestructor::with_destroy_context(inner.clone(), ());
+        let t_1 = t.clone();
+        assert!(Arc::ptr_eq(&t.context, &t_1.context));
+
+        drop(t);
+        drop(t_1);
+        assert_eq!(destroyed.load(Ordering::SeqCst), 1);
+    }
+
+    #[derive(Debug, Clone)]
+    struct InternalStats {
+        high_water: Arc<AtomicUsize>,
+    }
+
+    impl AtomicDestroyer for InternalStats {
+        fn on_destroy(&self) {}
+
+        fn on_destroy_with_stats(&self, stats: DestroyStats) {
+            self.high_water.store(stats.high_water, Ordering::SeqCst);
+        }
+    }
+
+    impl ContextDestroyer<()> for InternalStats {}
+
+    #[test]
+    fn test_default_on_destroy_ctx_with_stats() {
+        let high_water = Arc::new(AtomicUsize::new(0));
+        let t = AtomicDestructor::with_destroy_context(
+            InternalStats {
+                high_water: high_water.clone(),
+            },
+            (),
+        );
+        let t_1 = t.clone();
+
+        drop(t);
+        drop(t_1);
+        assert_eq!(high_water.load(Ordering::SeqCst), 2);
+    }
+}
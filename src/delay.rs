@@ -0,0 +1,252 @@
+// Copyright (c) 2024 Yuki Kishimoto
+// Distributed under the MIT software license
+
+//! Delayed destroyer
+
+use alloc::sync::Arc;
+use alloc::vec::Vec;
+use core::ops::{Deref, DerefMut};
+use core::sync::atomic::{AtomicU64, Ordering};
+use core::time::Duration;
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError, Sender};
+use std::thread;
+
+use crate::sync::{self, Mutex};
+use crate::{AtomicDestroyer, AtomicDestructor, DestroyStats};
+
+/// Destruction waiting for the delay to elapse
+#[derive(Debug)]
+struct Pending<T> {
+    /// Identifies the destruction (one per destroyed generation)
+    id: u64,
+    inner: T,
+    /// Dropped when cancelled, to wake up the waiting thread
+    _cancel: Sender<()>,
+}
+
+/// Destroyer that delays the destruction of the inner
+///
+/// When destroyed, the inner is destroyed from a background thread, after the delay.
+/// An error returned by [`AtomicDestroyer::on_destroy_fallible`] of the inner can't be retrieved: it's only logged.
+/// Useful to debounce rapid destroy-recreate cycles.
+///
+/// Every destroyed generation (see [`AtomicDestructor::reset`]) has its own pending destruction.
+///
+/// Created with [`AtomicDestructor::with_drop_delay`].
+#[derive(Debug, Clone)]
+pub struct DelayedDestroyer<T> {
+    inner: T,
+    delay: Duration,
+    next_id: Arc<AtomicU64>,
+    pending: Arc<Mutex<Vec<Pending<T>>>>,
+}
+
+impl<T> Deref for DelayedDestroyer<T> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        &self.inner
+    }
+}
+
+impl<T> DerefMut for DelayedDestroyer<T> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.inner
+    }
+}
+
+impl<T> DelayedDestroyer<T> {
+    /// Get the delay
+    pub fn delay(&self) -> Duration {
+        self.delay
+    }
+
+    /// Abort the most recent pending destruction and get back its inner
+    ///
+    /// The inner is not destroyed: the caller takes over its cleanup.
+    ///
+    /// Return `None` if nothing is pending (not destroyed yet, already cancelled or inner already destroyed).
+    pub fn cancel_pending_destroy(&self) -> Option<T> {
+        let pending: Pending<T> = sync::lock(&self.pending).pop()?;
+        Some(pending.inner)
+    }
+
+    /// Take the pending destruction `id`, if not cancelled
+    fn take_pending(&self, id: u64) -> Option<T> {
+        let mut pending = sync::lock(&self.pending);
+        let index: usize = pending.iter().position(|p| p.id == id)?;
+        Some(pending.remove(index).inner)
+    }
+}
+
+impl<T> AtomicDestroyer for DelayedDestroyer<T>
+where
+    T: AtomicDestroyer + Send + 'static,
+{
+    #[cfg(feature = "tracing")]
+    fn name(&self) -> Option<alloc::string::String> {
+        self.inner.name()
+    }
+
+    fn clone_allowed(&self) -> bool {
+        self.inner.clone_allowed()
+    }
+
+    fn on_clone(&self, counter: usize) {
+        self.inner.on_clone(counter);
+    }
+
+    fn on_any_drop(&self, remaining: usize) {
+        self.inner.on_any_drop(remaining);
+    }
+
+    fn abort_on_destroy_panic(&self) -> bool {
+        self.inner.abort_on_destroy_panic()
+    }
+
+    fn on_destroy(&self) {
+        self.on_destroy_with_stats(DestroyStats::default());
+    }
+
+    fn on_destroy_with_stats(&self, stats: DestroyStats) {
+        let id: u64 = self.next_id.fetch_add(1, Ordering::SeqCst);
+        let (tx, rx): (Sender<()>, Receiver<()>) = mpsc::channel();
+        sync::lock(&self.pending).push(Pending {
+            id,
+            inner: self.inner.clone(),
+            _cancel: tx,
+        });
+
+        let destroyer: Self = self.clone();
+        thread::spawn(move || {
+            // Disconnected: cancelled
+            if let Err(RecvTimeoutError::Timeout) = rx.recv_timeout(destroyer.delay) {
+                // Destroy, if not cancelled in the meantime
+                if let Some(inner) = destroyer.take_pending(id) {
+                    crate::destroy_detached(&inner, stats);
+                }
+            }
+        });
+    }
+}
+
+impl<T> AtomicDestructor<DelayedDestroyer<T>>
+where
+    T: AtomicDestroyer + Send + 'static,
+{
+    /// New wrapper that destroys the inner `delay` after the last instance is dropped
+    ///
+    /// The pending destruction can be aborted with [`DelayedDestroyer::cancel_pending_destroy`].
+    pub fn with_drop_delay(inner: T, delay: Duration) -> Self {
+        Self::new(DelayedDestroyer {
+            inner,
+            delay,
+            next_id: Arc::new(AtomicU64::new(0)),
+            pending: Arc::new(Mutex::new(Vec::new())),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Instant;
+
+    use super::*;
+    use crate::StealthClone;
+
+    #[derive(Debug, Clone)]
+    struct Internal {
+        id: u8,
+        destroyed: Sender<u8>,
+    }
+
+    impl AtomicDestroyer for Internal {
+        fn on_destroy(&self) {
+            let _ = self.destroyed.send(self.id);
+        }
+    }
+
+    #[derive(Debug, Clone)]
+    struct InternalStats {
+        destroyed: Sender<DestroyStats>,
+    }
+
+    impl AtomicDestroyer for InternalStats {
+        fn on_destroy(&self) {}
+
+        fn on_destroy_with_stats(&self, stats: DestroyStats) {
+            let _ = self.destroyed.send(stats);
+        }
+    }
+
+    #[test]
+    fn test_drop_delay() {
+        let (tx, rx) = mpsc::channel();
+        let delay = Duration::from_millis(20);
+        let t = AtomicDestructor::with_drop_delay(
+            Internal {
+                id: 1,
+                destroyed: tx,
+            },
+            delay,
+        );
+        let t_1 = t.clone();
+        assert_eq!(t.delay(), delay);
+
+        let now = Instant::now();
+        drop(t);
+        drop(t_1);
+
+        assert_eq!(rx.recv_timeout(Duration::from_secs(5)), Ok(1));
+        assert!(now.elapsed() >= delay);
+    }
+
+    #[test]
+    fn test_cancel_pending_destroy() {
+        let (tx, rx) = mpsc::channel();
+        let mut t = AtomicDestructor::with_drop_delay(
+            Internal {
+                id: 1,
+                destroyed: tx,
+            },
+            Duration::from_secs(60),
+        );
+        let observer = t.stealth_clone();
+
+        // Nothing pending
+        assert!(observer.cancel_pending_destroy().is_none());
+
+        assert!(t.try_destroy());
+        assert!(observer.is_destroyed());
+
+        // Each generation has its own pending destruction
+        t.id = 2;
+        assert_eq!(t.reset(), Ok(()));
+        assert!(t.try_destroy());
+
+        assert_eq!(observer.cancel_pending_destroy().unwrap().id, 2);
+        assert_eq!(observer.cancel_pending_destroy().unwrap().id, 1);
+        assert!(observer.cancel_pending_destroy().is_none());
+
+        // The cancelled inners are given back, not destroyed
+        drop(t);
+        drop(observer);
+        assert!(rx.recv_timeout(Duration::from_millis(50)).is_err());
+    }
+
+    #[test]
+    fn test_drop_delay_with_stats() {
+        let (tx, rx) = mpsc::channel();
+        let t = AtomicDestructor::with_drop_delay(
+            InternalStats { destroyed: tx },
+            Duration::from_millis(20),
+        );
+        let t_1 = t.clone();
+
+        drop(t);
+        drop(t_1);
+
+        let stats: DestroyStats = rx.recv_timeout(Duration::from_secs(5)).unwrap();
+        assert_eq!(stats.high_water, 2);
+    }
+}
@@ -0,0 +1,200 @@
+// Copyright (c) 2024 Yuki Kishimoto
+// Distributed under the MIT software license
+
+//! Epoch-based deferred destruction
+
+#[cfg(feature = "tracing")]
+use alloc::string::String;
+use core::ops::{Deref, DerefMut};
+
+use crate::{AtomicDestroyer, AtomicDestructor, DestroyStats};
+
+/// Atomic destructor with epoch-based deferred destruction
+///
+/// See [`EpochDeferred`].
+pub type EpochAtomicDestructor<T> = AtomicDestructor<EpochDeferred<T>>;
+
+/// Destroyer that defers the destruction of the inner using epoch-based reclamation
+///
+/// When the last instance is dropped, the destruction of the inner is scheduled with
+/// [`crossbeam_epoch::Guard::defer`], so it runs only once no pinned thread can still be accessing it.
+///
+/// The destructor is marked as destroyed immediately: only the cleanup is deferred.
+/// Deferred functions may never run if the process exits first.
+/// An error returned by [`AtomicDestroyer::on_destroy_fallible`] of the inner can't be retrieved: it's only logged.
+#[derive(Debug, Clone)]
+pub struct EpochDeferred<T>
+where
+    T: AtomicDestroyer,
+{
+    inner: T,
+}
+
+impl<T> Deref for EpochDeferred<T>
+where
+    T: AtomicDestroyer,
+{
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        &self.inner
+    }
+}
+
+impl<T> DerefMut for EpochDeferred<T>
+where
+    T: AtomicDestroyer,
+{
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.inner
+    }
+}
+
+impl<T> AtomicDestroyer for EpochDeferred<T>
+where
+    T: AtomicDestroyer + Send + 'static,
+{
+    #[cfg(feature = "tracing")]
+    fn name(&self) -> Option<String> {
+        self.inner.name()
+    }
+
+    fn clone_allowed(&self) -> bool {
+        self.inner.clone_allowed()
+    }
+
+    fn on_clone(&self, counter: usize) {
+        self.inner.on_clone(counter);
+    }
+
+    fn on_any_drop(&self, remaining: usize) {
+        self.inner.on_any_drop(remaining);
+    }
+
+    fn abort_on_destroy_panic(&self) -> bool {
+        self.inner.abort_on_destroy_panic()
+    }
+
+    fn on_destroy(&self) {
+        self.on_destroy_with_stats(DestroyStats::default());
+    }
+
+    fn on_destroy_with_stats(&self, stats: DestroyStats) {
+        let inner: T = self.inner.clone();
+        let guard = crossbeam_epoch::pin();
+        guard.defer(move || crate::destroy_detached(&inner, stats));
+    }
+}
+
+impl<T> EpochDeferred<T>
+where
+    T: AtomicDestroyer,
+{
+    /// Wrap inner
+    pub fn new(inner: T) -> Self {
+        Self { inner }
+    }
+}
+
+impl<T> AtomicDestructor<EpochDeferred<T>>
+where
+    T: AtomicDestroyer + Send + 'static,
+{
+    /// New wrapper with epoch-based deferred destruction
+    pub fn new_epoch(inner: T) -> Self {
+        Self::new(EpochDeferred::new(inner))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::sync::Arc;
+    use core::sync::atomic::{AtomicUsize, Ordering};
+
+    use super::*;
+    use crate::testing::CountingDestroyer;
+
+    #[derive(Debug, Clone, Default)]
+    struct InternalStats {
+        high_water: Arc<AtomicUsize>,
+    }
+
+    impl AtomicDestroyer for InternalStats {
+        fn on_destroy(&self) {}
+
+        fn on_destroy_with_stats(&self, stats: DestroyStats) {
+            self.high_water.store(stats.high_water, Ordering::SeqCst);
+        }
+    }
+
+    /// Advance the epoch until the deferred cleanup runs (`done`), up to a bounded number of attempts
+    #[track_caller]
+    fn flush_until<F>(done: F)
+    where
+        F: Fn() -> bool,
+    {
+        for _ in 0..10_000 {
+            if done() {
+                return;
+            }
+            crossbeam_epoch::pin().flush();
+        }
+
+        panic!("deferred cleanup not executed");
+    }
+
+    #[test]
+    fn test_epoch_deferred() {
+        let inner = CountingDestroyer::new();
+        let t = AtomicDestructor::new(EpochDeferred::new(inner.clone()));
+        let stealth = crate::StealthClone::stealth_clone(&t);
+
+        {
+            // A reader is pinned: the cleanup must not run
+            let reader = crossbeam_epoch::pin();
+
+            drop(t);
+            assert!(stealth.is_destroyed());
+
+            for _ in 0..128 {
+                reader.flush();
+            }
+            assert_eq!(inner.destroyed(), 0);
+        }
+
+        // Reader unpinned: the epoch can advance
+        flush_until(|| inner.destroyed() == 1);
+    }
+
+    #[test]
+    fn test_epoch_atomic_destructor() {
+        let inner = CountingDestroyer::new();
+        let t: EpochAtomicDestructor<CountingDestroyer> =
+            AtomicDestructor::new_epoch(inner.clone());
+        let t_1 = t.clone();
+
+        let reader = crossbeam_epoch::pin();
+
+        drop(t);
+        drop(t_1);
+
+        // Marked as destroyed, but the cleanup is still pending
+        assert_eq!(inner.destroyed(), 0);
+
+        drop(reader);
+
+        flush_until(|| inner.destroyed() == 1);
+    }
+
+    #[test]
+    fn test_epoch_deferred_with_stats() {
+        let inner = InternalStats::default();
+        let t = AtomicDestructor::new_epoch(inner.clone());
+        let t_1 = t.clone();
+
+        drop(t);
+        drop(t_1);
+
+        flush_until(|| inner.high_water.load(Ordering::SeqCst) == 2);
+    }
+}
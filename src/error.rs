@@ -0,0 +1,136 @@
+// Copyright (c) 2024 Yuki Kishimoto
+// Distributed under the MIT software license
+
+//! Errors
+
+#[cfg(feature = "std")]
+use alloc::boxed::Box;
+#[cfg(feature = "std")]
+use alloc::sync::Arc;
+use core::fmt;
+
+/// The destructor is already destroyed
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct DestroyedError;
+
+#[cfg(feature = "std")]
+impl std::error::Error for DestroyedError {}
+
+impl fmt::Display for DestroyedError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "already destroyed")
+    }
+}
+
+/// The destructor isn't the only non-stealth instance
+///
+/// Returned by [`AtomicDestructor::require_unique`](crate::AtomicDestructor::require_unique).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct SharedError {
+    pub(crate) others: usize,
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for SharedError {}
+
+impl fmt::Display for SharedError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "not unique: {} other instances alive", self.others)
+    }
+}
+
+impl SharedError {
+    /// Get the number of the other non-stealth instances alive
+    pub fn others(&self) -> usize {
+        self.others
+    }
+}
+
+/// Error returned by [`AtomicDestructor::try_map_in_place`](crate::AtomicDestructor::try_map_in_place)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TryMapError<E> {
+    /// The destructor is already destroyed
+    Destroyed(DestroyedError),
+    /// The map function failed
+    Map(E),
+}
+
+#[cfg(feature = "std")]
+impl<E> std::error::Error for TryMapError<E> where E: std::error::Error {}
+
+impl<E> fmt::Display for TryMapError<E>
+where
+    E: fmt::Display,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Destroyed(e) => write!(f, "{}", e),
+            Self::Map(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+impl<E> From<DestroyedError> for TryMapError<E> {
+    fn from(e: DestroyedError) -> Self {
+        Self::Destroyed(e)
+    }
+}
+
+/// Error returned by [`AtomicDestructor::reset`](crate::AtomicDestructor::reset)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ResetError {
+    /// The current generation isn't destroyed
+    NotDestroyed,
+    /// The lifecycle is poisoned: clear it with [`AtomicDestructor::clear_poison`](crate::AtomicDestructor::clear_poison)
+    Poisoned,
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for ResetError {}
+
+impl fmt::Display for ResetError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NotDestroyed => write!(f, "not destroyed"),
+            Self::Poisoned => write!(f, "poisoned"),
+        }
+    }
+}
+
+/// Error returned by [`AtomicDestroyer::on_destroy_fallible`](crate::AtomicDestroyer::on_destroy_fallible)
+///
+/// Retrieved with [`AtomicDestructor::last_destroy_error`](crate::AtomicDestructor::last_destroy_error).
+#[cfg(feature = "std")]
+#[derive(Debug, Clone)]
+pub struct DestroyError {
+    inner: Arc<dyn std::error::Error + Send + Sync>,
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for DestroyError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        Some(&*self.inner)
+    }
+}
+
+#[cfg(feature = "std")]
+impl fmt::Display for DestroyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "destruction failed: {}", self.inner)
+    }
+}
+
+#[cfg(feature = "std")]
+impl From<Box<dyn std::error::Error + Send + Sync>> for DestroyError {
+    fn from(e: Box<dyn std::error::Error + Send + Sync>) -> Self {
+        Self { inner: e.into() }
+    }
+}
+
+#[cfg(feature = "std")]
+impl DestroyError {
+    /// Get the error returned by the destroyer
+    pub fn inner(&self) -> &(dyn std::error::Error + Send + Sync + 'static) {
+        &*self.inner
+    }
+}
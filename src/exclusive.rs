@@ -0,0 +1,152 @@
+// Copyright (c) 2024 Yuki Kishimoto
+// Distributed under the MIT software license
+
+//! Exclusive reference
+
+use core::fmt;
+use core::ops::{Deref, DerefMut};
+
+use crate::error::SharedError;
+use crate::{AtomicDestroyer, AtomicDestructor};
+
+/// Exclusive access to the inner of the only non-stealth instance
+///
+/// The wrapper is mutably borrowed, so it can't be cloned while the reference is alive.
+/// Stealth instances may still create counted clones: the uniqueness is checked again on drop
+/// (a warning is logged with the `tracing` feature) or explicitly with [`ExclusiveRef::release`].
+///
+/// Returned by [`AtomicDestructor::require_unique`].
+pub struct ExclusiveRef<'a, T>
+where
+    T: AtomicDestroyer,
+{
+    destructor: &'a mut AtomicDestructor<T>,
+}
+
+impl<'a, T> fmt::Debug for ExclusiveRef<'a, T>
+where
+    T: AtomicDestroyer,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("ExclusiveRef")
+            .field(self.destructor)
+            .finish()
+    }
+}
+
+impl<'a, T> Deref for ExclusiveRef<'a, T>
+where
+    T: AtomicDestroyer,
+{
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        self.destructor.inner()
+    }
+}
+
+impl<'a, T> DerefMut for ExclusiveRef<'a, T>
+where
+    T: AtomicDestroyer,
+{
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        self.destructor.inner_mut()
+    }
+}
+
+impl<'a, T> Drop for ExclusiveRef<'a, T>
+where
+    T: AtomicDestroyer,
+{
+    fn drop(&mut self) {
+        #[cfg(feature = "tracing")]
+        if !self.destructor.is_unique() {
+            tracing::warn!(
+                group_id = self.destructor.group_id(),
+                "Exclusive reference released while not unique anymore"
+            );
+        }
+    }
+}
+
+impl<'a, T> ExclusiveRef<'a, T>
+where
+    T: AtomicDestroyer,
+{
+    /// Release the reference, checking that the instance is still unique
+    pub fn release(self) -> Result<(), SharedError> {
+        check_unique(self.destructor)
+    }
+}
+
+fn check_unique<T>(destructor: &AtomicDestructor<T>) -> Result<(), SharedError>
+where
+    T: AtomicDestroyer,
+{
+    if destructor.is_unique() {
+        return Ok(());
+    }
+
+    let counter: usize = destructor.counter();
+    Err(SharedError {
+        others: if destructor.is_stealth() {
+            counter
+        } else {
+            counter.saturating_sub(1)
+        },
+    })
+}
+
+impl<T> AtomicDestructor<T>
+where
+    T: AtomicDestroyer,
+{
+    /// Get exclusive access to the inner, if this is the only non-stealth instance
+    ///
+    /// For APIs that must be called only by the unique owner of a resource.
+    /// Return an error with the number of the other non-stealth instances if not unique (or stealth).
+    pub fn require_unique(&mut self) -> Result<ExclusiveRef<'_, T>, SharedError> {
+        check_unique(self)?;
+        Ok(ExclusiveRef { destructor: self })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{NoopDestroyer, StealthClone};
+
+    #[test]
+    fn test_require_unique() {
+        let mut t = AtomicDestructor::new(NoopDestroyer(1));
+
+        let mut exclusive = t.require_unique().unwrap();
+        exclusive.0 = 2;
+        assert!(exclusive.release().is_ok());
+        assert_eq!(t.0, 2);
+
+        let t_1 = t.clone();
+        let t_2 = t.clone();
+        let err = t.require_unique().unwrap_err();
+        assert_eq!(err.others(), 2);
+        assert_eq!(err.to_string(), "not unique: 2 other instances alive");
+
+        let mut stealth = t.stealth_clone();
+        assert_eq!(stealth.require_unique().unwrap_err().others(), 3);
+
+        drop(t_1);
+        drop(t_2);
+        assert!(t.require_unique().is_ok());
+    }
+
+    #[test]
+    fn test_require_unique_clone_from_stealth() {
+        let mut t = AtomicDestructor::new(NoopDestroyer(()));
+        let stealth = t.stealth_clone();
+
+        let exclusive = t.require_unique().unwrap();
+        let t_1 = stealth.clone();
+        assert_eq!(exclusive.release().unwrap_err().others(), 1);
+        drop(t_1);
+    }
+}
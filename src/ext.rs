@@ -0,0 +1,123 @@
+// Copyright (c) 2024 Yuki Kishimoto
+// Distributed under the MIT software license
+
+//! Atomic destructor extension
+
+use crate::{AtomicDestroyer, AtomicDestructor};
+
+/// Option-like combinators for [`AtomicDestructor`]
+pub trait AtomicDestructorExt<T>: Sized
+where
+    T: AtomicDestroyer,
+{
+    /// Call `f` with the inner, if not destroyed
+    ///
+    /// Return `None` if destroyed, otherwise the result of `f`.
+    fn and_then<U, F>(&self, f: F) -> Option<U>
+    where
+        F: FnOnce(&T) -> Option<U>;
+
+    /// Return `None` if destroyed or if the predicate fails
+    ///
+    /// Like [`Option::filter`], `self` is consumed: when the predicate fails it's dropped,
+    /// so if it was the last non-stealth instance, [`AtomicDestroyer::on_destroy`] is called.
+    /// Check the predicate on a reference (i.e., with [`AtomicDestructorExt::and_then`]) to keep the instance.
+    fn filter<P>(self, predicate: P) -> Option<Self>
+    where
+        P: Fn(&T) -> bool;
+
+    /// Return `self` if not destroyed, otherwise a new instance created from `factory` output
+    fn or_new<F>(self, factory: F) -> Self
+    where
+        F: Fn() -> T;
+}
+
+impl<T> AtomicDestructorExt<T> for AtomicDestructor<T>
+where
+    T: AtomicDestroyer,
+{
+    fn and_then<U, F>(&self, f: F) -> Option<U>
+    where
+        F: FnOnce(&T) -> Option<U>,
+    {
+        if self.is_destroyed() {
+            return None;
+        }
+
+        f(self.inner())
+    }
+
+    fn filter<P>(self, predicate: P) -> Option<Self>
+    where
+        P: Fn(&T) -> bool,
+    {
+        if self.is_destroyed() || !predicate(self.inner()) {
+            return None;
+        }
+
+        Some(self)
+    }
+
+    fn or_new<F>(self, factory: F) -> Self
+    where
+        F: Fn() -> T,
+    {
+        if self.is_destroyed() {
+            return AtomicDestructor::new(factory());
+        }
+
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::NoopDestroyer;
+    use crate::StealthClone;
+
+    fn destroyed(value: u8) -> AtomicDestructor<NoopDestroyer<u8>> {
+        let t = AtomicDestructor::new(NoopDestroyer(value));
+        assert!(t.compare_and_destroy(1).is_ok());
+        t
+    }
+
+    #[test]
+    fn test_and_then() {
+        let t = AtomicDestructor::new(NoopDestroyer(1));
+        assert_eq!(t.and_then(|i| Some(i.0 + 1)), Some(2));
+        assert_eq!(t.and_then(|_| None::<u8>), None);
+
+        let t = destroyed(1);
+        assert_eq!(t.and_then(|i| Some(i.0)), None);
+    }
+
+    #[test]
+    fn test_filter() {
+        let t = AtomicDestructor::new(NoopDestroyer(1));
+        let t = t.filter(|i| i.0 == 1).unwrap();
+        assert!(t.filter(|i| i.0 == 2).is_none());
+
+        let t = destroyed(1);
+        assert!(t.filter(|i| i.0 == 1).is_none());
+
+        // The last instance is destroyed by a failed predicate
+        let t = AtomicDestructor::new(NoopDestroyer(1));
+        let observer = t.stealth_clone();
+        assert!(t.filter(|i| i.0 == 2).is_none());
+        assert!(observer.is_destroyed());
+    }
+
+    #[test]
+    fn test_or_new() {
+        let t = AtomicDestructor::new(NoopDestroyer(1));
+        let t = t.or_new(|| NoopDestroyer(2));
+        assert_eq!(t.0, 1);
+
+        let t = destroyed(1);
+        let t = t.or_new(|| NoopDestroyer(2));
+        assert_eq!(t.0, 2);
+        assert!(!t.is_destroyed());
+        assert_eq!(t.counter(), 1);
+    }
+}
@@ -0,0 +1,390 @@
+// Copyright (c) 2024 Yuki Kishimoto
+// Distributed under the MIT software license
+
+//! Async destruction notifications
+
+use alloc::sync::Arc;
+use alloc::vec::Vec;
+use core::future::Future;
+use core::pin::Pin;
+use core::sync::atomic::Ordering;
+use core::task::{Context, Poll, Waker};
+
+use crate::sync;
+use crate::{AnyAtomicDestructor, AtomicDestroyer, AtomicDestructor, State};
+
+/// Register the waker of the task, if not already registered
+fn register(wakers: &mut Vec<Waker>, cx: &Context<'_>) {
+    if !wakers.iter().any(|waker| waker.will_wake(cx.waker())) {
+        wakers.push(cx.waker().clone());
+    }
+}
+
+/// Future that resolves when an [`AtomicDestructor`] is destroyed
+///
+/// Resolves once [`AtomicDestroyer::on_destroy`] and the post listeners completed.
+/// Doesn't keep the destructor alive and doesn't depend on a specific async runtime.
+///
+/// Nothing is registered until polled: dropping the future leaves nothing behind, except the waker of its last poll,
+/// released at the next drop of an instance. Clones can be polled from different tasks.
+#[derive(Debug, Clone)]
+pub struct DestructorFuture {
+    state: Arc<State>,
+    generation: usize,
+}
+
+impl Future for DestructorFuture {
+    type Output = ();
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        // Checked under the lock, so a concurrent destruction can't be missed
+        let mut wakers = sync::lock(&self.state.drop_wakers);
+        self.state.expect_drop();
+
+        // A reset implies that the generation was destroyed
+        let completed: bool = self.generation != self.state.generation.load(Ordering::SeqCst)
+            || sync::lock(&self.state.post_listeners).is_none();
+        if completed {
+            return Poll::Ready(());
+        }
+
+        register(&mut wakers, cx);
+        Poll::Pending
+    }
+}
+
+/// Future returned by [`AtomicDestructor::shrink_to_last`]
+#[derive(Debug)]
+pub struct ShrinkToLast<'a, T>
+where
+    T: AtomicDestroyer,
+{
+    destructor: &'a AtomicDestructor<T>,
+}
+
+impl<'a, T> Future for ShrinkToLast<'a, T>
+where
+    T: AtomicDestroyer,
+{
+    type Output = ();
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        // Checked under the lock, so a concurrent drop can't be missed
+        let mut wakers = sync::lock(&self.destructor.state.drop_wakers);
+        self.destructor.state.expect_drop();
+        if self.destructor.is_unique() || self.destructor.is_destroyed() {
+            return Poll::Ready(());
+        }
+
+        register(&mut wakers, cx);
+        Poll::Pending
+    }
+}
+
+/// Barrier that resolves when all the added destructors are destroyed
+///
+/// Destructors of different types can be added to the same barrier.
+#[derive(Debug, Clone, Default)]
+pub struct AtomicDestructorBarrier {
+    futures: Vec<DestructorFuture>,
+}
+
+impl AtomicDestructorBarrier {
+    /// New barrier
+    pub fn new<I>(futures: I) -> Self
+    where
+        I: IntoIterator<Item = DestructorFuture>,
+    {
+        Self {
+            futures: futures.into_iter().collect(),
+        }
+    }
+
+    /// Add destructor to the barrier
+    pub fn add<T>(&mut self, destructor: &AtomicDestructor<T>)
+    where
+        T: AtomicDestroyer,
+    {
+        self.futures.push(destructor.wait_destroyed());
+    }
+
+    /// Number of destructors in the barrier
+    pub fn len(&self) -> usize {
+        self.futures.len()
+    }
+
+    /// Check if the barrier is empty
+    pub fn is_empty(&self) -> bool {
+        self.futures.is_empty()
+    }
+
+    /// Wait until all the destructors are destroyed
+    pub async fn wait(self) {
+        for future in self.futures.into_iter() {
+            future.await;
+        }
+    }
+}
+
+impl FromIterator<DestructorFuture> for AtomicDestructorBarrier {
+    fn from_iter<I>(iter: I) -> Self
+    where
+        I: IntoIterator<Item = DestructorFuture>,
+    {
+        Self::new(iter)
+    }
+}
+
+/// Future returned by [`select_first_destroyed`]
+#[derive(Debug, Clone)]
+pub struct SelectFirstDestroyed {
+    futures: Vec<DestructorFuture>,
+}
+
+impl Future for SelectFirstDestroyed {
+    type Output = usize;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        for (index, future) in self.futures.iter_mut().enumerate() {
+            if Pin::new(future).poll(cx).is_ready() {
+                return Poll::Ready(index);
+            }
+        }
+        Poll::Pending
+    }
+}
+
+/// Wait for the first destroyed destructor and return its index
+///
+/// If more destructors are already destroyed, the lowest index is returned.
+/// The destructors are subscribed immediately, so they can be dropped before awaiting.
+/// If `destructors` is empty, the future never resolves.
+pub fn select_first_destroyed(
+    destructors: &[Arc<dyn AnyAtomicDestructor>],
+) -> SelectFirstDestroyed {
+    SelectFirstDestroyed {
+        futures: destructors.iter().map(|d| d.wait_destroyed()).collect(),
+    }
+}
+
+impl<T> AtomicDestructor<T>
+where
+    T: AtomicDestroyer,
+{
+    /// Get a future that resolves when the destructor is destroyed
+    ///
+    /// Resolves immediately if already destroyed. See [`DestructorFuture`].
+    pub fn wait_destroyed(&self) -> DestructorFuture {
+        DestructorFuture {
+            state: self.state.clone(),
+            generation: self.generation,
+        }
+    }
+
+    /// Get a future that resolves when this is the only non-stealth instance, or when destroyed
+    ///
+    /// Async version of [`AtomicDestructor::shrink_to_last_blocking`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if stealth: it can never be the last instance.
+    #[track_caller]
+    pub fn shrink_to_last(&self) -> ShrinkToLast<'_, T> {
+        assert!(!self.is_stealth(), "stealth instance can't be the last one");
+        ShrinkToLast { destructor: self }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::mpsc;
+    use std::task::Wake;
+    use std::thread::{self, Thread};
+    use std::time::Duration;
+
+    use super::*;
+    use crate::StealthClone;
+
+    #[derive(Debug, Clone)]
+    struct InternalTestingDestroyer;
+
+    impl AtomicDestroyer for InternalTestingDestroyer {
+        fn on_destroy(&self) {}
+    }
+
+    #[derive(Debug, Clone)]
+    struct InternalTestingOther;
+
+    impl AtomicDestroyer for InternalTestingOther {
+        fn on_destroy(&self) {}
+    }
+
+    struct ThreadWaker(Thread);
+
+    impl Wake for ThreadWaker {
+        fn wake(self: Arc<Self>) {
+            self.0.unpark();
+        }
+    }
+
+    fn block_on<F>(future: F) -> F::Output
+    where
+        F: Future,
+    {
+        let waker = Waker::from(Arc::new(ThreadWaker(thread::current())));
+        let mut cx = Context::from_waker(&waker);
+        let mut future = Box::pin(future);
+        loop {
+            match future.as_mut().poll(&mut cx) {
+                Poll::Ready(output) => return output,
+                Poll::Pending => thread::park(),
+            }
+        }
+    }
+
+    #[test]
+    fn test_wait_destroyed() {
+        let destructor = AtomicDestructor::new(InternalTestingDestroyer);
+        let future = destructor.wait_destroyed();
+
+        let handle = thread::spawn(move || {
+            thread::sleep(Duration::from_millis(20));
+            drop(destructor);
+        });
+
+        block_on(future);
+        handle.join().unwrap();
+
+        // Already destroyed
+        let destructor = AtomicDestructor::new(InternalTestingDestroyer);
+        let stealth = destructor.stealth_clone();
+        drop(destructor);
+        block_on(stealth.wait_destroyed());
+    }
+
+    #[test]
+    fn test_wait_destroyed_clones() {
+        let destructor = AtomicDestructor::new(InternalTestingDestroyer);
+        let future = destructor.wait_destroyed();
+
+        // Polled from different tasks
+        let (tx, rx) = mpsc::channel();
+        let handles: Vec<_> = (0..2)
+            .map(|_| {
+                let future = future.clone();
+                let tx = tx.clone();
+                thread::spawn(move || {
+                    block_on(future);
+                    tx.send(()).unwrap();
+                })
+            })
+            .collect();
+
+        thread::sleep(Duration::from_millis(20));
+        drop(destructor);
+
+        for handle in handles.into_iter() {
+            handle.join().unwrap();
+        }
+        assert_eq!(rx.try_iter().count(), 2);
+    }
+
+    #[test]
+    fn test_wait_destroyed_no_leak() {
+        let destructor = AtomicDestructor::new(InternalTestingDestroyer);
+        for _ in 0..100 {
+            drop(destructor.wait_destroyed());
+        }
+
+        // Nothing registered
+        assert!(sync::lock(&destructor.state.post_listeners)
+            .as_ref()
+            .unwrap()
+            .is_empty());
+        assert!(sync::lock(&destructor.state.drop_wakers).is_empty());
+    }
+
+    #[test]
+    fn test_barrier() {
+        let a = AtomicDestructor::new(InternalTestingDestroyer);
+        let b = AtomicDestructor::new(InternalTestingOther);
+        let c = AtomicDestructor::new(InternalTestingDestroyer);
+
+        let mut barrier = AtomicDestructorBarrier::default();
+        barrier.add(&a);
+        barrier.add(&b);
+        barrier.add(&c);
+        assert_eq!(barrier.len(), 3);
+
+        let (tx, rx) = mpsc::channel();
+
+        let handle = thread::spawn(move || {
+            drop(b);
+            thread::sleep(Duration::from_millis(10));
+            drop(c);
+            thread::sleep(Duration::from_millis(30));
+            tx.send(()).unwrap();
+            drop(a);
+        });
+
+        block_on(barrier.wait());
+
+        // The last destructor is dropped after the message
+        assert!(rx.try_recv().is_ok());
+        handle.join().unwrap();
+    }
+
+    #[test]
+    fn test_shrink_to_last() {
+        let destructor = AtomicDestructor::new(InternalTestingDestroyer);
+        block_on(destructor.shrink_to_last());
+
+        let holders: Vec<_> = (0..4).map(|_| destructor.clone()).collect();
+        let handle = thread::spawn(move || {
+            for holder in holders.into_iter() {
+                thread::sleep(Duration::from_millis(10));
+                drop(holder);
+            }
+        });
+
+        block_on(destructor.shrink_to_last());
+        assert!(destructor.is_unique());
+        handle.join().unwrap();
+    }
+
+    #[test]
+    fn test_select_first_destroyed() {
+        for first in 0..2 {
+            let a = AtomicDestructor::new(InternalTestingDestroyer);
+            let b = AtomicDestructor::new(InternalTestingOther);
+
+            // Stealth handles: they don't keep the destructors alive
+            let handles: Vec<Arc<dyn AnyAtomicDestructor>> =
+                vec![Arc::new(a.stealth_clone()), Arc::new(b.stealth_clone())];
+            let future = select_first_destroyed(&handles);
+            drop(handles);
+
+            let (tx, rx) = mpsc::channel();
+            let handle = thread::spawn(move || {
+                let (first_destructor, second_destructor): (
+                    Box<dyn AnyAtomicDestructor + Send>,
+                    Box<dyn AnyAtomicDestructor + Send>,
+                ) = if first == 0 {
+                    (Box::new(a), Box::new(b))
+                } else {
+                    (Box::new(b), Box::new(a))
+                };
+
+                drop(first_destructor);
+
+                // The second one is dropped only after the first has been observed
+                rx.recv().unwrap();
+                drop(second_destructor);
+            });
+
+            assert_eq!(block_on(future), first);
+            tx.send(()).unwrap();
+            handle.join().unwrap();
+        }
+    }
+}
@@ -0,0 +1,127 @@
+// Copyright (c) 2024 Yuki Kishimoto
+// Distributed under the MIT software license
+
+//! Background cleanup hooks
+
+use alloc::boxed::Box;
+use alloc::string::String;
+use alloc::vec;
+use alloc::vec::Vec;
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::mpsc::{self, Sender};
+use std::thread;
+
+use once_cell::sync::OnceCell;
+
+use crate::sync::{self, Mutex};
+use crate::{AtomicDestroyer, AtomicDestructor};
+
+/// Hook executed in background after the destruction
+pub(crate) type GcHook = Box<dyn Fn() + Send>;
+
+/// Global background worker, spawned on first use
+static WORKER: OnceCell<Mutex<Sender<Vec<GcHook>>>> = OnceCell::new();
+
+fn worker() -> &'static Mutex<Sender<Vec<GcHook>>> {
+    WORKER.get_or_init(|| {
+        let (tx, rx) = mpsc::channel::<Vec<GcHook>>();
+
+        thread::Builder::new()
+            .name(String::from("atomic-destructor-gc"))
+            .spawn(move || {
+                for hooks in rx.into_iter() {
+                    for hook in hooks.into_iter() {
+                        // A panicking hook must not kill the worker
+                        let _ = panic::catch_unwind(AssertUnwindSafe(hook));
+                    }
+                }
+            })
+            .expect("failed to spawn gc worker");
+
+        Mutex::new(tx)
+    })
+}
+
+/// Run the hooks on the background worker
+pub(crate) fn schedule(hooks: Vec<GcHook>) {
+    if hooks.is_empty() {
+        return;
+    }
+
+    // The worker never exits, so the receiver is always alive
+    let _ = sync::lock(worker()).send(hooks);
+}
+
+impl<T> AtomicDestructor<T>
+where
+    T: AtomicDestroyer,
+{
+    /// Register a hook executed in background, after [`AtomicDestroyer::on_destroy`] completes
+    ///
+    /// For slow cleanups that shouldn't block the dropping thread (i.e., disk compaction, cache eviction).
+    /// The hooks run in order on a global background thread, spawned on first use.
+    ///
+    /// If already destroyed, the hook is scheduled immediately.
+    pub fn register_gc_hook<F>(&self, hook: F)
+    where
+        F: Fn() + Send + 'static,
+    {
+        let mut hooks = sync::lock(&self.state.gc_hooks);
+        match hooks.as_mut() {
+            Some(hooks) => hooks.push(Box::new(hook)),
+            None => {
+                drop(hooks);
+                schedule(vec![Box::new(hook)]);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::sync::Arc;
+    use core::time::Duration;
+    use std::sync::mpsc::Receiver;
+
+    use super::*;
+    use crate::StealthClone;
+
+    #[derive(Debug, Clone)]
+    struct Internal {
+        events: Sender<&'static str>,
+    }
+
+    impl AtomicDestroyer for Internal {
+        fn on_destroy(&self) {
+            thread::sleep(Duration::from_millis(50));
+            self.events.send("destroyed").unwrap();
+        }
+    }
+
+    #[test]
+    fn test_register_gc_hook() {
+        let (tx, rx): (Sender<&'static str>, Receiver<&'static str>) = mpsc::channel();
+        let t = AtomicDestructor::new(Internal { events: tx.clone() });
+        let observer = t.stealth_clone();
+        let dropping_thread = thread::current().id();
+
+        let events = Arc::new(Mutex::new(tx));
+        t.register_gc_hook(move || {
+            assert_ne!(thread::current().id(), dropping_thread);
+            sync::lock(&events).send("gc").unwrap();
+        });
+
+        // Not destroyed yet
+        assert!(rx.recv_timeout(Duration::from_millis(100)).is_err());
+
+        drop(t);
+        assert_eq!(rx.recv_timeout(Duration::from_secs(5)), Ok("destroyed"));
+        assert_eq!(rx.recv_timeout(Duration::from_secs(5)), Ok("gc"));
+
+        // Already destroyed
+        let (tx, rx): (Sender<&'static str>, Receiver<&'static str>) = mpsc::channel();
+        let tx = Arc::new(Mutex::new(tx));
+        observer.register_gc_hook(move || sync::lock(&tx).send("late gc").unwrap());
+        assert_eq!(rx.recv_timeout(Duration::from_secs(5)), Ok("late gc"));
+    }
+}
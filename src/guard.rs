@@ -0,0 +1,174 @@
+// Copyright (c) 2024 Yuki Kishimoto
+// Distributed under the MIT software license
+
+//! Destroy guard
+
+use core::fmt;
+use core::sync::atomic::Ordering;
+
+use crate::saturating::SaturatingInt;
+use crate::{AtomicDestroyer, AtomicDestructor, DestroyTrigger};
+
+/// Guard that defers the destruction until it's dropped
+///
+/// Returned by [`AtomicDestructor::destroy_guard`].
+pub struct DestroyGuard<T>
+where
+    T: AtomicDestroyer,
+{
+    destructor: AtomicDestructor<T>,
+}
+
+impl<T> fmt::Debug for DestroyGuard<T>
+where
+    T: AtomicDestroyer,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("DestroyGuard")
+            .field("destructor", &self.destructor)
+            .finish()
+    }
+}
+
+impl<T> Drop for DestroyGuard<T>
+where
+    T: AtomicDestroyer,
+{
+    fn drop(&mut self) {
+        let state = &self.destructor.state;
+
+        // Release guard
+        let value: usize = state.guards.saturating_decrement(Ordering::SeqCst);
+
+        // Run the deferred destruction, if it was triggered while guarded
+        if value == 0
+            && state.pending.load(Ordering::SeqCst)
+            && (state.trigger == DestroyTrigger::FirstDrop
+                || state.poisoned.load(Ordering::SeqCst)
+                || state.counter.load(Ordering::SeqCst) == 0)
+        {
+            self.destructor.destroy();
+        }
+    }
+}
+
+impl<T> DestroyGuard<T>
+where
+    T: AtomicDestroyer,
+{
+    /// Acquire guard for a stealth destructor
+    pub(crate) fn new(destructor: AtomicDestructor<T>) -> Self {
+        destructor
+            .state
+            .guards
+            .saturating_increment(Ordering::SeqCst);
+        Self { destructor }
+    }
+}
+
+/// Run a block while holding a [`DestroyGuard`]
+///
+/// Sugar for [`AtomicDestructor::destroy_guard`]: the destruction can't run until the block exits,
+/// even with an early `return` or `?` (the guard is released on the way out).
+///
+/// # Example
+///
+/// ```rust
+/// use atomic_destructor::{destroy_guard, AtomicDestructor, NoopDestroyer, StealthClone};
+///
+/// let t = AtomicDestructor::new(NoopDestroyer(()));
+/// let observer = t.stealth_clone();
+///
+/// destroy_guard!(observer, {
+///     drop(t);
+///     assert!(!observer.is_destroyed());
+/// });
+///
+/// assert!(observer.is_destroyed());
+/// ```
+#[macro_export]
+macro_rules! destroy_guard {
+    ($destructor:expr, $body:block) => {{
+        let _guard = $crate::AtomicDestructor::destroy_guard(&$destructor);
+        $body
+    }};
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::CountingDestroyer;
+    use crate::StealthClone;
+
+    #[test]
+    fn test_destroy_guard() {
+        let inner = CountingDestroyer::new();
+        let t = AtomicDestructor::new(inner.clone());
+        let t_1 = t.clone();
+
+        let guard = t.destroy_guard();
+        assert_eq!(t.counter(), 2);
+
+        drop(t);
+        drop(t_1);
+        assert_eq!(inner.destroyed(), 0);
+        assert!(!guard.destructor.is_destroyed());
+
+        drop(guard);
+        assert_eq!(inner.destroyed(), 1);
+    }
+
+    #[test]
+    fn test_destroy_guard_released_before_last_drop() {
+        let inner = CountingDestroyer::new();
+        let t = AtomicDestructor::new(inner.clone());
+
+        let guard = t.destroy_guard();
+        drop(guard);
+        assert_eq!(inner.destroyed(), 0);
+
+        drop(t);
+        assert_eq!(inner.destroyed(), 1);
+    }
+
+    #[test]
+    fn test_destroy_guard_untracked() {
+        let inner = CountingDestroyer::new();
+        let factory = AtomicDestructor::new_untracked(inner.clone());
+
+        // Nothing was dropped: releasing the guard must not destroy
+        let guard = factory.destroy_guard();
+        drop(guard);
+        assert!(!factory.is_destroyed());
+        assert_eq!(inner.destroyed(), 0);
+    }
+
+    fn destroyed_in_guard(
+        t: AtomicDestructor<CountingDestroyer>,
+        observer: &AtomicDestructor<CountingDestroyer>,
+        bail: bool,
+    ) -> bool {
+        destroy_guard!(observer, {
+            drop(t);
+
+            if bail {
+                return observer.is_destroyed();
+            }
+
+            observer.is_destroyed()
+        })
+    }
+
+    #[test]
+    fn test_destroy_guard_macro() {
+        for bail in [true, false] {
+            let inner = CountingDestroyer::new();
+            let t = AtomicDestructor::new(inner.clone());
+            let observer = t.stealth_clone();
+
+            assert!(!destroyed_in_guard(t, &observer, bail));
+            assert!(observer.is_destroyed());
+            assert_eq!(inner.destroyed(), 1);
+        }
+    }
+}
@@ -2,20 +2,159 @@
 // Distributed under the MIT software license
 
 //! Atomic destructor
+//!
+//! Run a destruction logic when the last clone of a value is dropped.
+//!
+//! For simple cases, use [`AtomicDestructor::from_closure`], without implementing [`AtomicDestroyer`]:
+//!
+//! ```rust
+//! use atomic_destructor::AtomicDestructor;
+//!
+//! let client = AtomicDestructor::from_closure(String::from("client"), |name| {
+//!     println!("Shutting down {}", name);
+//! });
+//! let client_1 = client.clone();
+//!
+//! drop(client);
+//! drop(client_1); // Prints "Shutting down client"
+//! ```
+//!
+//! [`Deref`] always gives access to the inner. To access it only while not destroyed (i.e., from a stealth clone),
+//! use [`AtomicDestructor::peek`]:
+//!
+//! ```rust
+//! use atomic_destructor::{AtomicDestructor, StealthClone};
+//!
+//! let client = AtomicDestructor::from_closure(String::from("client"), |_| {});
+//! let observer = client.stealth_clone();
+//! assert_eq!(observer.peek().map(|c| c.len()), Some(6));
+//!
+//! drop(client);
+//! assert!(observer.peek().is_none());
+//! ```
+//!
+//! To get an owned copy of the inner (i.e., to pass a standalone `T` downstream),
+//! use [`AtomicDestructor::to_owned_inner`] instead of the confusing `(*wrapper).clone()`.
 
+#![cfg_attr(not(any(test, feature = "std")), no_std)]
 #![forbid(unsafe_code)]
 #![warn(missing_docs)]
 
 extern crate alloc;
 
+#[cfg(feature = "std")]
+use alloc::boxed::Box;
+#[cfg(feature = "tracing")]
+use alloc::format;
+#[cfg(feature = "tracing")]
+use alloc::string::String;
 use alloc::sync::Arc;
+use alloc::vec::Vec;
+#[cfg(feature = "std")]
+use core::any::Any;
 use core::fmt::{self, Debug};
 use core::ops::{Deref, DerefMut};
-use core::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use core::pin::Pin;
+use core::sync::atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering};
+#[cfg(feature = "std")]
+use core::task::Waker;
+#[cfg(feature = "std")]
+use core::time::Duration;
+#[cfg(feature = "thread-tracking")]
+use std::collections::HashMap;
+#[cfg(feature = "std")]
+use std::panic::{self, AssertUnwindSafe};
+#[cfg(feature = "std")]
+use std::process;
+#[cfg(feature = "thread-tracking")]
+use std::thread::{self, ThreadId};
+#[cfg(feature = "std")]
+use std::time::Instant;
 
+mod any;
+mod arc_inner;
+mod borrowed;
+mod builder;
+#[cfg(feature = "std")]
+mod cell;
+mod checkpoint;
+#[cfg(feature = "async")]
+mod cleanup;
+mod closure;
+mod context;
+#[cfg(feature = "std")]
+mod delay;
+#[cfg(feature = "epoch")]
+mod epoch;
+mod error;
+mod exclusive;
+mod ext;
+#[cfg(feature = "async")]
+mod future;
+#[cfg(feature = "gc")]
+mod gc;
+mod guard;
+mod lifecycle;
+mod manager;
+mod noop;
+mod observer;
 mod saturating;
+#[cfg(feature = "stats")]
+mod stats;
+#[cfg(feature = "std")]
+mod sync;
+#[cfg(any(test, feature = "testing"))]
+mod testing;
+#[cfg(feature = "std")]
+mod timeout;
+#[cfg(feature = "tokio")]
+mod tokio;
+#[cfg(feature = "std")]
+mod watchdog;
 
-use self::saturating::SaturatingUsize;
+pub use self::any::{AnyAtomicDestructor, DynDestroyer};
+pub use self::arc_inner::{ArcInner, ArcInnerDestructor};
+pub use self::borrowed::BorrowedAtomicDestructor;
+pub use self::builder::{AtomicDestructorBuilder, DestroyTrigger};
+#[cfg(feature = "std")]
+pub use self::cell::AtomicDestructorCell;
+pub use self::checkpoint::DestructorCheckpoint;
+#[cfg(feature = "async")]
+pub use self::cleanup::{AsyncCleanupDestroyer, AsyncDestroyer, BoxFuture, CleanupQueue};
+pub use self::closure::{ClosureDestroyer, FullClosureDestroyer};
+pub use self::context::{ContextDestroyer, ContextualDestructor, WithDestroyContext};
+#[cfg(feature = "std")]
+pub use self::delay::DelayedDestroyer;
+#[cfg(feature = "epoch")]
+pub use self::epoch::{EpochAtomicDestructor, EpochDeferred};
+#[cfg(feature = "std")]
+pub use self::error::DestroyError;
+pub use self::error::{DestroyedError, ResetError, SharedError, TryMapError};
+pub use self::exclusive::ExclusiveRef;
+pub use self::ext::AtomicDestructorExt;
+#[cfg(feature = "async")]
+pub use self::future::{
+    select_first_destroyed, AtomicDestructorBarrier, DestructorFuture, SelectFirstDestroyed,
+    ShrinkToLast,
+};
+pub use self::guard::DestroyGuard;
+pub use self::lifecycle::LifecycleDisplay;
+pub use self::manager::{DestructorManager, DestructorManagerStatus};
+pub use self::noop::NoopDestroyer;
+pub use self::observer::ObserverWeak;
+use self::saturating::SaturatingInt;
+#[cfg(feature = "stats")]
+pub use self::stats::LifetimeStats;
+#[cfg(feature = "std")]
+use self::sync::{Condvar, Mutex};
+#[cfg(feature = "testing")]
+pub use self::testing::{assert_destroyed_once, CountingDestroyer};
+#[cfg(feature = "std")]
+pub use self::timeout::TimeoutDestroyer;
+#[cfg(feature = "tokio")]
+pub use self::tokio::SpawnBlockingWrapper;
+#[cfg(feature = "std")]
+pub use self::watchdog::WatchdogDestroyer;
 
 /// Stealth clone
 pub trait StealthClone {
@@ -33,19 +172,522 @@ pub trait AtomicDestroyer: Debug + Clone {
         None
     }
 
+    /// Check if a non-stealth clone can be created (default: `true`)
+    ///
+    /// Called before every clone: i.e., to allow cloning only before the initialization completes.
+    /// If `false`, [`Clone::clone`] panics and [`AtomicDestructor::try_clone`] returns `None`.
+    fn clone_allowed(&self) -> bool {
+        true
+    }
+
+    /// Called on the new instance every time a non-stealth clone is created, with the new counter value
+    fn on_clone(&self, _counter: usize) {}
+
+    /// Called every time a non-stealth instance is dropped, with the remaining counter value
+    ///
+    /// When `remaining` is `0`, it's called right before the destruction.
+    fn on_any_drop(&self, _remaining: usize) {}
+
     /// Instructions to execute when all instances are dropped
+    ///
+    /// The destructor is already marked as destroyed when this is called,
+    /// so instances dropped from here (i.e. captured clones) don't trigger a new destruction.
     fn on_destroy(&self);
+
+    /// Like [`AtomicDestroyer::on_destroy`], with the [final statistics](DestroyStats) of the lifecycle
+    ///
+    /// By default, it calls [`AtomicDestroyer::on_destroy`].
+    fn on_destroy_with_stats(&self, _stats: DestroyStats) {
+        self.on_destroy();
+    }
+
+    /// Abort the process if [`AtomicDestroyer::on_destroy`] panics (default: `false`)
+    ///
+    /// For critical resources (i.e., transaction managers), where unwinding from a half-done cleanup is worse than terminating.
+    #[cfg(feature = "std")]
+    fn abort_on_destroy_panic(&self) -> bool {
+        false
+    }
+
+    /// Fallible version of [`AtomicDestroyer::on_destroy_with_stats`]
+    ///
+    /// This is the one called by the destructor: by default, it calls [`AtomicDestroyer::on_destroy_with_stats`] and returns `Ok(())`.
+    /// Override it for cleanups that can fail (i.e., flushing a buffer to disk):
+    /// the error is stored and can be retrieved with [`AtomicDestructor::last_destroy_error`].
+    #[cfg(feature = "std")]
+    fn on_destroy_fallible(
+        &self,
+        stats: DestroyStats,
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        self.on_destroy_with_stats(stats);
+        Ok(())
+    }
+}
+
+/// Final statistics of a lifecycle, passed to [`AtomicDestroyer::on_destroy_with_stats`]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct DestroyStats {
+    /// Number of non-stealth clones created (the first instance excluded)
+    pub total_clones: usize,
+    /// Max number of non-stealth instances alive at the same time
+    pub high_water: usize,
+    /// If the counter reached `usize::MAX`
+    pub saturated: bool,
+}
+
+/// Source of the group IDs
+static NEXT_GROUP_ID: AtomicU64 = AtomicU64::new(0);
+
+/// Inner of the type-erased [heartbeats](AtomicDestructor::heartbeat)
+///
+/// Does nothing on destruction: the original inner is destroyed by the lifecycle owner.
+/// Can't be constructed outside of this crate.
+#[cfg(feature = "std")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Heartbeat(());
+
+#[cfg(feature = "std")]
+impl AtomicDestroyer for Heartbeat {
+    fn on_destroy(&self) {}
+}
+
+/// Callback executed during the destruction
+#[cfg(feature = "std")]
+type Listener = Box<dyn FnOnce() + Send>;
+
+/// Callback executed every time a stealth instance is dropped
+#[cfg(feature = "std")]
+type StealthDropListener = Arc<dyn Fn() + Send + Sync>;
+
+/// Poison the lifecycle if dropped while unwinding from [`AtomicDestroyer::on_destroy`]
+///
+/// The destruction is completed anyway (see [`State::finish_destroy`]), so the waiters don't hang.
+#[cfg(feature = "std")]
+struct PoisonOnPanic<'a>(&'a State);
+
+#[cfg(feature = "std")]
+impl<'a> Drop for PoisonOnPanic<'a> {
+    fn drop(&mut self) {
+        if std::thread::panicking() {
+            self.0.poisoned.store(true, Ordering::SeqCst);
+            self.0.finish_destroy();
+        }
+    }
+}
+
+/// Lifecycle state shared by all the instances
+///
+/// Opaque: it can only be obtained from an existing destructor, with [`AtomicDestructor::state`],
+/// and adopted by another one with [`AtomicDestructor::from_parts`].
+pub struct State {
+    /// Unique ID of the lifecycle
+    group_id: u64,
+    /// Increased on every [`AtomicDestructor::reset`]
+    generation: AtomicUsize,
+    /// Increased on every mutation of the inner (see [`AtomicDestructor::version`])
+    version: AtomicU64,
+    /// Set by the only thread allowed to run the destruction
+    destroying: AtomicBool,
+    destroyed: AtomicBool,
+    counter: AtomicUsize,
+    /// Number of stealth instances alive
+    stealth_counter: AtomicUsize,
+    /// Number of held [`DestroyGuard`]
+    guards: AtomicUsize,
+    /// Set when the counter reached zero while a guard was held
+    pending: AtomicBool,
+    /// Max value of the counter
+    max_clones: Option<usize>,
+    /// When the destruction is triggered
+    trigger: DestroyTrigger,
+    /// Report the destruction while stealth instances are alive
+    forbid_stealth_during_destroy: bool,
+    /// Number of non-stealth clones created
+    total_clones: AtomicUsize,
+    /// Max value reached by the counter
+    high_water: AtomicUsize,
+    /// Set when the counter reached `usize::MAX`
+    saturated: AtomicBool,
+    /// Set by [`AtomicDestructor::poison`]
+    poisoned: AtomicBool,
+    /// Set by [`DestructorManager::seal`]: no new non-stealth instances
+    sealed: AtomicBool,
+    /// Instances of other destructors, released after the destruction
+    #[cfg(feature = "std")]
+    dependencies: Mutex<Vec<Box<dyn Any + Send + Sync>>>,
+    /// Lifecycle owner, destroyed instead of the inner of the last dropped instance, if joined
+    ///
+    /// Set by [`AtomicDestructor::heartbeat`] and [`AtomicDestructor::add_sibling`].
+    #[cfg(feature = "std")]
+    owner: Mutex<Option<DynDestroyer>>,
+    /// Time taken by [`AtomicDestroyer::on_destroy`]
+    #[cfg(feature = "std")]
+    destroy_duration: Mutex<Option<Duration>>,
+    /// Error returned by [`AtomicDestroyer::on_destroy_fallible`]
+    #[cfg(feature = "std")]
+    destroy_error: Mutex<Option<DestroyError>>,
+    /// Listeners executed before [`AtomicDestroyer::on_destroy`] (`None` once executed)
+    #[cfg(feature = "std")]
+    pre_listeners: Mutex<Option<Vec<Listener>>>,
+    /// Listeners executed after [`AtomicDestroyer::on_destroy`] (`None` once executed)
+    #[cfg(feature = "std")]
+    post_listeners: Mutex<Option<Vec<Listener>>>,
+    /// Hooks scheduled in background after [`AtomicDestroyer::on_destroy`] (`None` once scheduled)
+    #[cfg(feature = "gc")]
+    gc_hooks: Mutex<Option<Vec<gc::GcHook>>>,
+    /// Listeners executed when a stealth instance is dropped
+    #[cfg(feature = "std")]
+    stealth_drop_listeners: Mutex<Vec<StealthDropListener>>,
+    /// Set once a stealth drop listener is registered: otherwise, the stealth drops skip the lock
+    #[cfg(feature = "std")]
+    has_stealth_drop_listeners: AtomicBool,
+    /// Tasks waiting for a non-stealth drop or for the destruction (also the lock of `dropped`)
+    #[cfg(feature = "std")]
+    drop_wakers: Mutex<Vec<Waker>>,
+    /// Set by the waiters of `drop_wakers` and `dropped`, cleared when notified: otherwise, the drops skip the lock
+    #[cfg(feature = "std")]
+    has_drop_waiters: AtomicBool,
+    /// Notified on every non-stealth drop
+    #[cfg(feature = "std")]
+    dropped: Condvar,
+    /// Non-stealth instances per creating thread
+    #[cfg(feature = "thread-tracking")]
+    threads: Mutex<HashMap<ThreadId, usize>>,
+    /// Set by [`AtomicDestructor::with_stats`]
+    #[cfg(feature = "stats")]
+    stats: Option<LifetimeStats>,
+    /// When the lifecycle was first created (not changed by [`AtomicDestructor::reset`])
+    #[cfg(feature = "timestamps")]
+    created_at: Instant,
+    /// When the lifecycle was marked as destroyed
+    #[cfg(feature = "timestamps")]
+    destroyed_at: Mutex<Option<Instant>>,
+}
+
+impl fmt::Debug for State {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("State")
+            .field("group_id", &self.group_id)
+            .field("destroyed", &self.destroyed)
+            .field("counter", &self.counter)
+            .field("stealth_counter", &self.stealth_counter)
+            .finish_non_exhaustive()
+    }
+}
+
+impl State {
+    fn new(counter: usize) -> Self {
+        Self {
+            group_id: NEXT_GROUP_ID.fetch_add(1, Ordering::Relaxed),
+            generation: AtomicUsize::new(0),
+            version: AtomicU64::new(0),
+            destroying: AtomicBool::new(false),
+            destroyed: AtomicBool::new(false),
+            counter: AtomicUsize::new(counter),
+            stealth_counter: AtomicUsize::new(0),
+            guards: AtomicUsize::new(0),
+            pending: AtomicBool::new(false),
+            max_clones: None,
+            trigger: DestroyTrigger::LastDrop,
+            forbid_stealth_during_destroy: false,
+            total_clones: AtomicUsize::new(0),
+            high_water: AtomicUsize::new(counter),
+            saturated: AtomicBool::new(false),
+            poisoned: AtomicBool::new(false),
+            sealed: AtomicBool::new(false),
+            #[cfg(feature = "std")]
+            dependencies: Mutex::new(Vec::new()),
+            #[cfg(feature = "std")]
+            owner: Mutex::new(None),
+            #[cfg(feature = "std")]
+            destroy_duration: Mutex::new(None),
+            #[cfg(feature = "std")]
+            destroy_error: Mutex::new(None),
+            #[cfg(feature = "std")]
+            pre_listeners: Mutex::new(Some(Vec::new())),
+            #[cfg(feature = "std")]
+            post_listeners: Mutex::new(Some(Vec::new())),
+            #[cfg(feature = "gc")]
+            gc_hooks: Mutex::new(Some(Vec::new())),
+            #[cfg(feature = "std")]
+            stealth_drop_listeners: Mutex::new(Vec::new()),
+            #[cfg(feature = "std")]
+            has_stealth_drop_listeners: AtomicBool::new(false),
+            #[cfg(feature = "std")]
+            drop_wakers: Mutex::new(Vec::new()),
+            #[cfg(feature = "std")]
+            has_drop_waiters: AtomicBool::new(false),
+            #[cfg(feature = "std")]
+            dropped: Condvar::new(),
+            #[cfg(feature = "thread-tracking")]
+            threads: Mutex::new(HashMap::new()),
+            #[cfg(feature = "stats")]
+            stats: None,
+            #[cfg(feature = "timestamps")]
+            created_at: Instant::now(),
+            #[cfg(feature = "timestamps")]
+            destroyed_at: Mutex::new(None),
+        }
+    }
+
+    /// Increase the counter by `n`, respecting the clone limit
+    ///
+    /// Once the destruction is claimed, the new instances are already destroyed: the counter is not increased.
+    ///
+    /// Return the new value or `None` if the limit would be exceeded.
+    fn increase(&self, n: usize) -> Option<usize> {
+        if self.destroying.load(Ordering::SeqCst) {
+            return Some(self.counter.load(Ordering::SeqCst));
+        }
+
+        if self.sealed.load(Ordering::SeqCst) {
+            return None;
+        }
+
+        let value: usize = match self.max_clones {
+            Some(max) => self.counter.checked_add(n, max, Ordering::SeqCst)?,
+            None => self.counter.saturating_add(n, Ordering::SeqCst),
+        };
+        self.check_saturation(value);
+        self.total_clones.fetch_add(n, Ordering::SeqCst);
+        self.high_water.fetch_max(value, Ordering::SeqCst);
+
+        #[cfg(feature = "stats")]
+        if let Some(stats) = &self.stats {
+            stats.record_clones(n, value);
+        }
+
+        Some(value)
+    }
+
+    /// Panic for a clone refused by [`State::increase`]
+    #[track_caller]
+    fn refuse_clone(&self) -> ! {
+        if self.sealed.load(Ordering::SeqCst) {
+            panic!("lifecycle sealed");
+        }
+
+        panic!("clone limit reached")
+    }
+
+    fn destroy_stats(&self) -> DestroyStats {
+        DestroyStats {
+            total_clones: self.total_clones.load(Ordering::SeqCst),
+            high_water: self.high_water.load(Ordering::SeqCst),
+            saturated: self.saturated.load(Ordering::SeqCst),
+        }
+    }
+
+    fn check_saturation(&self, value: usize) {
+        if value == usize::MAX {
+            self.saturated.store(true, Ordering::SeqCst);
+        }
+    }
+
+    /// Add listener to the list or, if the list was already executed, call it immediately
+    #[cfg(feature = "std")]
+    fn subscribe(listeners: &Mutex<Option<Vec<Listener>>>, listener: Listener) {
+        let mut listeners = sync::lock(listeners);
+        match listeners.as_mut() {
+            Some(listeners) => listeners.push(listener),
+            None => {
+                drop(listeners);
+                listener();
+            }
+        }
+    }
+
+    /// Execute the listeners (outside the lock)
+    #[cfg(feature = "std")]
+    fn notify(listeners: &Mutex<Option<Vec<Listener>>>) {
+        let listeners: Option<Vec<Listener>> = sync::lock(listeners).take();
+        for listener in listeners.into_iter().flatten() {
+            listener();
+        }
+    }
+
+    /// Add `n` instances to the current thread entry
+    #[cfg(feature = "thread-tracking")]
+    fn track_thread(&self, n: usize) -> Option<ThreadId> {
+        let id: ThreadId = thread::current().id();
+        let mut threads = sync::lock(&self.threads);
+        let count: &mut usize = threads.entry(id).or_insert(0);
+        *count = count.saturating_add(n);
+        Some(id)
+    }
+
+    /// Remove an instance from the `id` thread entry
+    #[cfg(feature = "thread-tracking")]
+    fn untrack_thread(&self, id: ThreadId) {
+        let mut threads = sync::lock(&self.threads);
+        if let Some(count) = threads.get_mut(&id) {
+            *count = count.saturating_sub(1);
+            if *count == 0 {
+                threads.remove(&id);
+            }
+        }
+    }
+
+    /// Execute the stealth drop listeners (outside the lock)
+    #[cfg(feature = "std")]
+    fn notify_stealth_drop(&self) {
+        if !self.has_stealth_drop_listeners.load(Ordering::SeqCst) {
+            return;
+        }
+
+        let listeners: Vec<StealthDropListener> = sync::lock(&self.stealth_drop_listeners).clone();
+        for listener in listeners.iter() {
+            listener();
+        }
+    }
+
+    /// Announce a waiter for a non-stealth drop
+    ///
+    /// Call it holding the `drop_wakers` lock, before checking the awaited condition:
+    /// a drop that changes the condition afterwards sees the flag and notifies.
+    #[cfg(feature = "std")]
+    fn expect_drop(&self) {
+        self.has_drop_waiters.store(true, Ordering::SeqCst);
+    }
+
+    /// Wake the threads and the tasks waiting for a non-stealth drop
+    #[cfg(feature = "std")]
+    fn notify_drop(&self) {
+        // Nobody is waiting
+        if !self.has_drop_waiters.load(Ordering::SeqCst) {
+            return;
+        }
+
+        let wakers: Vec<Waker> = {
+            let mut wakers = sync::lock(&self.drop_wakers);
+            // The waiters still pending announce themselves again
+            self.has_drop_waiters.store(false, Ordering::SeqCst);
+            core::mem::take(&mut *wakers)
+        };
+        self.dropped.notify_all();
+        for waker in wakers.into_iter() {
+            waker.wake();
+        }
+    }
+
+    /// Release the unused capacity of the listeners, hooks and dependencies storage
+    #[cfg(feature = "std")]
+    fn shrink_listeners(&self) {
+        for listeners in [&self.pre_listeners, &self.post_listeners].iter() {
+            if let Some(listeners) = sync::lock(listeners).as_mut() {
+                listeners.shrink_to_fit();
+            }
+        }
+
+        #[cfg(feature = "gc")]
+        if let Some(hooks) = sync::lock(&self.gc_hooks).as_mut() {
+            hooks.shrink_to_fit();
+        }
+
+        sync::lock(&self.stealth_drop_listeners).shrink_to_fit();
+        sync::lock(&self.dependencies).shrink_to_fit();
+    }
+
+    /// Total capacity of the listeners storage
+    #[cfg(all(test, feature = "std"))]
+    fn listeners_capacity(&self) -> usize {
+        let mut capacity: usize = 0;
+        for listeners in [&self.pre_listeners, &self.post_listeners].iter() {
+            if let Some(listeners) = sync::lock(listeners).as_ref() {
+                capacity += listeners.capacity();
+            }
+        }
+        capacity + sync::lock(&self.stealth_drop_listeners).capacity()
+    }
+
+    /// Complete the destruction, after the inner one (even if it panicked)
+    ///
+    /// Run the post listeners, release the dependencies, wake the waiters and schedule the gc hooks.
+    #[cfg(feature = "std")]
+    fn finish_destroy(&self) {
+        State::notify(&self.post_listeners);
+        self.release_dependencies();
+
+        // The counter is frozen: the waiters for the last instance can stop
+        self.notify_drop();
+
+        #[cfg(feature = "gc")]
+        if let Some(hooks) = sync::lock(&self.gc_hooks).take() {
+            gc::schedule(hooks);
+        }
+    }
+
+    /// Release the dependencies (outside the lock)
+    #[cfg(feature = "std")]
+    fn release_dependencies(&self) {
+        let dependencies: Vec<Box<dyn Any + Send + Sync>> =
+            core::mem::take(&mut *sync::lock(&self.dependencies));
+        drop(dependencies);
+    }
+}
+
+/// Raw state snapshot, returned by [`AtomicDestructor::__state`]
+///
+/// **Unstable**: not part of the public API, may change or be removed in any release.
+#[doc(hidden)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RawState {
+    /// Counter
+    pub counter: usize,
+    /// Destroyed flag
+    pub destroyed: bool,
+    /// Stealth flag of the instance
+    pub stealth: bool,
 }
 
 /// Atomic destructor
+///
+/// # Thread safety
+///
+/// `Send` and `Sync` are derived automatically (no `unsafe impl`):
+/// the shared state is always thread-safe, so `AtomicDestructor<T>` is `Send`/`Sync` exactly when `T` is.
+/// Since every instance owns a clone of `T` and [`AtomicDestroyer::on_destroy`] runs on the thread that drops the last instance,
+/// `T` must be `Send + Sync` to share the destructor across threads.
+///
+/// [`AtomicDestroyer::on_destroy`] is called at most once per lifecycle, even if more threads try to destroy at the same time:
+/// the first one to claim the destruction runs it, the others return without waiting for it to complete.
+///
+/// # FFI
+///
+/// The crate forbids `unsafe` code, so it doesn't provide raw parts conversions.
+/// To pass an instance across an FFI boundary (i.e., as the user data of a C callback), box it:
+/// the raw pointer owns the instance, so it keeps being counted until it's reconstructed and dropped.
+///
+/// ```rust
+/// use atomic_destructor::{AtomicDestructor, NoopDestroyer};
+///
+/// let t = AtomicDestructor::new(NoopDestroyer(()));
+/// let ptr: *mut AtomicDestructor<NoopDestroyer<()>> = Box::into_raw(Box::new(t.clone()));
+/// assert_eq!(t.counter(), 2);
+///
+/// // SAFETY: `ptr` comes from `Box::into_raw` and is reconstructed only once
+/// let t_1 = unsafe { Box::from_raw(ptr) };
+/// drop(t_1);
+/// assert_eq!(t.counter(), 1);
+/// ```
 pub struct AtomicDestructor<T>
 where
     T: AtomicDestroyer,
 {
-    destroyed: Arc<AtomicBool>,
-    counter: Arc<AtomicUsize>,
+    state: Arc<State>,
     stealth: bool,
-    inner: T,
+    /// Name of the instance, set with [`AtomicDestructor::clone_and_name`]
+    label: Option<&'static str>,
+    /// Generation of the lifecycle the instance belongs to (see [`AtomicDestructor::reset`])
+    generation: usize,
+    /// Joined the lifecycle of another inner type: destroyed through the lifecycle owner, if any
+    #[cfg(feature = "std")]
+    joined: bool,
+    /// Thread that created the instance (`None` if stealth)
+    #[cfg(feature = "thread-tracking")]
+    thread: Option<ThreadId>,
+    /// `None` only once moved out (see [`AtomicDestructor::transfer_inner`]), right before being dropped
+    inner: Option<T>,
 }
 
 impl<T> Deref for AtomicDestructor<T>
@@ -54,8 +696,28 @@ where
 {
     type Target = T;
 
+    /// Get the inner
+    ///
+    /// The inner is returned even if destroyed: use [`AtomicDestructor::peek`] to check it.
+    ///
+    /// With `tracing` and debug assertions enabled, a warning is emitted when used after the destruction.
     fn deref(&self) -> &Self::Target {
-        &self.inner
+        #[cfg(all(feature = "tracing", debug_assertions))]
+        if self.is_destroyed() {
+            match &self.log_name() {
+                Some(name) => tracing::warn!(
+                    group_id = self.group_id(),
+                    "{} dereferenced after destruction",
+                    name
+                ),
+                None => tracing::warn!(
+                    group_id = self.group_id(),
+                    "Destructor dereferenced after destruction"
+                ),
+            }
+        }
+
+        self.inner()
     }
 }
 
@@ -64,43 +726,72 @@ where
     T: AtomicDestroyer,
 {
     fn deref_mut(&mut self) -> &mut Self::Target {
-        &mut self.inner
+        self.inner_mut()
     }
 }
 
+/// Pinning an [`AtomicDestructor`] never pins the inner: there is no structural pin projection.
+///
+/// The lifecycle state lives in a separate allocation, so moving the wrapper doesn't affect the counter
+/// nor the stealth instances. Safe to hold in self-referential (pinned) futures, whatever `T` is.
+impl<T> Unpin for AtomicDestructor<T> where T: AtomicDestroyer {}
+
 impl<T> fmt::Debug for AtomicDestructor<T>
 where
     T: AtomicDestroyer,
 {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        f.debug_struct("AtomicDestructor")
-            .field("destroyed", &self.destroyed)
-            .field("counter", &self.counter)
-            .field("stealth", &self.stealth)
-            .field("inner", &self.inner)
+        let mut debug = f.debug_struct("AtomicDestructor");
+
+        #[cfg(feature = "tracing")]
+        if let Some(name) = &self.inner().name() {
+            debug.field("name", name);
+        }
+
+        if let Some(label) = self.label {
+            debug.field("clone_name", &label);
+        }
+
+        debug
+            .field("counter", &self.counter())
+            .field("is_destroyed", &self.is_destroyed())
+            .field("is_stealth", &self.is_stealth())
+            .field("is_unique", &self.is_unique())
+            .field("inner", &self.inner())
             .finish()
     }
 }
 
+impl<T> fmt::Pointer for AtomicDestructor<T>
+where
+    T: AtomicDestroyer,
+{
+    /// Format the address of the shared state
+    ///
+    /// All the instances sharing the same state (i.e., clones) are formatted identically.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Pointer::fmt(&self.state, f)
+    }
+}
+
 impl<T> Clone for AtomicDestructor<T>
 where
     T: AtomicDestroyer,
 {
+    /// Clone and increase the counter.
+    ///
+    /// If already destroyed (i.e., cloned from [`AtomicDestroyer::on_destroy`]), the clone is already destroyed too:
+    /// the counter is not increased and dropping the clone does nothing.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the [clone limit](AtomicDestructor::clone_limit) is reached or if [cloning is not allowed](AtomicDestroyer::clone_allowed).
+    /// Use [`AtomicDestructor::try_clone`] to handle it.
+    #[track_caller]
     fn clone(&self) -> Self {
-        // Increase counter
-        let _value: usize = self.counter.saturating_increment(Ordering::SeqCst);
-
-        #[cfg(feature = "tracing")]
-        if let Some(name) = &self.inner.name() {
-            tracing::trace!("{} cloned: atomic counter increased to {}", name, _value);
-        }
-
-        // Clone
-        Self {
-            destroyed: self.destroyed.clone(),
-            counter: self.counter.clone(),
-            stealth: false,
-            inner: self.inner.clone(),
+        match self.try_clone() {
+            Some(clone) => clone,
+            None => self.refuse_clone(),
         }
     }
 }
@@ -110,11 +801,21 @@ where
     T: AtomicDestroyer,
 {
     fn stealth_clone(&self) -> Self {
+        // Increase stealth counter
+        self.state
+            .stealth_counter
+            .saturating_increment(Ordering::SeqCst);
+
         Self {
-            destroyed: self.destroyed.clone(),
-            counter: self.counter.clone(),
+            state: self.state.clone(),
             stealth: true,
-            inner: self.inner.clone(),
+            label: self.label,
+            generation: self.generation,
+            #[cfg(feature = "std")]
+            joined: self.joined,
+            #[cfg(feature = "thread-tracking")]
+            thread: None,
+            inner: Some(self.inner().clone()),
         }
     }
 }
@@ -125,43 +826,72 @@ where
 {
     fn drop(&mut self) {
         if self.is_stealth() {
+            // Decrease stealth counter
+            self.state
+                .stealth_counter
+                .saturating_decrement(Ordering::SeqCst);
+
             #[cfg(feature = "tracing")]
-            tracing::trace!("Tried to drop stealth destructor, ignore.");
+            tracing::trace!(
+                group_id = self.group_id(),
+                "Tried to drop stealth destructor, ignore."
+            );
+
+            #[cfg(feature = "std")]
+            self.state.notify_stealth_drop();
+
+            return;
+        }
+
+        #[cfg(feature = "thread-tracking")]
+        if let Some(id) = self.thread {
+            self.state.untrack_thread(id);
+        }
 
+        // Moved out: already destroyed, and no inner to call
+        if self.inner.is_none() {
             return;
         }
 
         if self.is_destroyed() {
             #[cfg(feature = "tracing")]
-            if let Some(name) = &self.inner.name() {
-                tracing::trace!("{} already destroyed.", name);
+            if let Some(name) = &self.log_name() {
+                tracing::trace!(group_id = self.group_id(), "{} already destroyed.", name);
             }
         } else {
             // Decrease counter
-            let value: usize = self.counter.saturating_decrement(Ordering::SeqCst);
+            let (previous, value) = self
+                .state
+                .counter
+                .saturating_decrement_prev(Ordering::SeqCst);
 
-            #[cfg(feature = "tracing")]
-            if let Some(name) = &self.inner.name() {
-                tracing::trace!("{} dropped: atomic counter decreased to {}", name, value);
+            #[cfg(feature = "stats")]
+            if let Some(stats) = &self.state.stats {
+                stats.record_drop();
             }
 
-            // Check if it's time for destruction
-            if value == 0 {
-                #[cfg(feature = "tracing")]
-                if let Some(name) = &self.inner.name() {
-                    tracing::trace!("Destroying {} ...", name);
-                }
+            self.inner().on_any_drop(value);
 
-                // Destroy
-                self.inner.on_destroy();
+            #[cfg(feature = "std")]
+            self.state.notify_drop();
 
-                // Mark as destroyed
-                self.destroyed.store(true, Ordering::SeqCst);
+            #[cfg(feature = "tracing")]
+            if let Some(name) = &self.log_name() {
+                tracing::trace!(
+                    group_id = self.group_id(),
+                    "{} dropped: atomic counter decreased from {} to {}",
+                    name,
+                    previous,
+                    value
+                );
+            }
 
-                #[cfg(feature = "tracing")]
-                if let Some(name) = &self.inner.name() {
-                    tracing::trace!("{} destroyed", name);
-                }
+            #[cfg(not(feature = "tracing"))]
+            let _ = previous;
+
+            // Check if it's time for destruction
+            if value == 0 || self.state.trigger == DestroyTrigger::FirstDrop || self.is_poisoned() {
+                self.destroy_or_defer();
             }
         }
     }
@@ -173,115 +903,3532 @@ where
 {
     /// New wrapper
     pub fn new(inner: T) -> Self {
+        let state: Arc<State> = Arc::new(State::new(1));
         Self {
-            destroyed: Arc::new(AtomicBool::new(false)),
-            counter: Arc::new(AtomicUsize::new(1)),
+            #[cfg(feature = "thread-tracking")]
+            thread: state.track_thread(1),
+            state,
             stealth: false,
-            inner,
+            label: None,
+            generation: 0,
+            #[cfg(feature = "std")]
+            joined: false,
+            inner: Some(inner),
         }
     }
 
-    /// Get counter
-    pub fn counter(&self) -> usize {
-        self.counter.load(Ordering::SeqCst)
+    /// New untracked wrapper
+    ///
+    /// The returned instance is stealth, so it doesn't count toward the lifecycle:
+    /// only its (non-stealth) clones do. When all the clones are dropped, the inner is destroyed.
+    ///
+    /// Useful for factories/templates that hand out clones but must not keep the inner alive.
+    pub fn new_untracked(inner: T) -> Self {
+        let state: State = State::new(0);
+        state.stealth_counter.store(1, Ordering::SeqCst);
+
+        Self {
+            state: Arc::new(state),
+            stealth: true,
+            label: None,
+            generation: 0,
+            #[cfg(feature = "std")]
+            joined: false,
+            #[cfg(feature = "thread-tracking")]
+            thread: None,
+            inner: Some(inner),
+        }
     }
 
-    /// Check if destroyed
-    pub fn is_destroyed(&self) -> bool {
-        self.destroyed.load(Ordering::SeqCst)
+    /// New instance adopting an existing lifecycle state
+    ///
+    /// Allows wrapping the same lifecycle in multiple typed views. Unless `stealth`, the new instance is counted like a clone:
+    /// [`AtomicDestroyer::on_destroy`] is called only on the inner of the last dropped instance, whatever its type.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the [clone limit](AtomicDestructor::clone_limit) is reached.
+    #[track_caller]
+    pub fn from_parts(inner: T, state: Arc<State>, stealth: bool) -> Self {
+        let generation: usize = state.generation.load(Ordering::SeqCst);
+
+        if stealth {
+            state.stealth_counter.saturating_increment(Ordering::SeqCst);
+        } else if state.increase(1).is_none() {
+            state.refuse_clone();
+        }
+
+        Self {
+            #[cfg(feature = "thread-tracking")]
+            thread: if stealth { None } else { state.track_thread(1) },
+            state,
+            stealth,
+            label: None,
+            generation,
+            #[cfg(feature = "std")]
+            joined: false,
+            inner: Some(inner),
+        }
     }
 
-    /// Check if is stealth (stealth cloned, not subject to counter increase/decrease)
-    pub fn is_stealth(&self) -> bool {
-        self.stealth
+    /// New builder
+    pub fn builder(inner: T) -> AtomicDestructorBuilder<T> {
+        AtomicDestructorBuilder::new(inner)
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+    /// Clone, respecting the [clone limit](AtomicDestructor::clone_limit)
+    ///
+    /// Return `None` if the limit is reached, if [cloning is not allowed](AtomicDestroyer::clone_allowed)
+    /// or if [sealed](DestructorManager::seal).
+    pub fn try_clone(&self) -> Option<Self> {
+        self.try_clone_counted().map(|(clone, _)| clone)
+    }
 
-    #[derive(Debug, Clone)]
-    struct InternalTestingStealth;
+    /// Clone, if not destroyed
+    ///
+    /// Return `None` if destroyed, [poisoned](AtomicDestructor::poison) or if the [clone limit](AtomicDestructor::clone_limit) is reached.
+    pub fn clone_if_alive(&self) -> Option<Self> {
+        if self.is_destroyed() || self.is_poisoned() {
+            return None;
+        }
 
-    impl AtomicDestroyer for InternalTestingStealth {
-        fn on_destroy(&self) {}
+        self.try_clone()
     }
 
-    #[derive(Clone)]
-    struct TestingStealth {
-        inner: AtomicDestructor<InternalTestingStealth>,
+    /// Clone and return the counter value produced by the increment
+    ///
+    /// The canonical way to get both: the returned value is the one written by the atomic increment that created the clone,
+    /// so it's always race-free. Don't call [`AtomicDestructor::counter`] after cloning:
+    /// concurrent clones/drops may change the counter in between.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the [clone limit](AtomicDestructor::clone_limit) is reached or if [cloning is not allowed](AtomicDestroyer::clone_allowed).
+    #[track_caller]
+    pub fn counted_clone(&self) -> (Self, usize) {
+        match self.try_clone_counted() {
+            Some(res) => res,
+            None => self.refuse_clone(),
+        }
     }
 
-    impl StealthClone for TestingStealth {
-        fn stealth_clone(&self) -> Self {
-            Self {
-                inner: self.inner.stealth_clone(),
-            }
+    /// Panic for a clone refused by [`AtomicDestructor::try_clone`]
+    #[track_caller]
+    fn refuse_clone(&self) -> ! {
+        if !self.inner().clone_allowed() {
+            panic!("clone not allowed");
         }
+
+        self.state.refuse_clone()
     }
 
-    impl TestingStealth {
-        pub fn new() -> Self {
-            Self {
-                inner: AtomicDestructor::new(InternalTestingStealth),
-            }
+    /// Increase the counter by `n`, unless stale (see [`State::increase`])
+    fn increase(&self, n: usize) -> Option<usize> {
+        if self.is_stale() {
+            return Some(self.counter());
         }
+
+        self.state.increase(n)
     }
 
-    #[test]
-    fn test_clone() {
-        let t = TestingStealth::new();
-        assert_eq!(t.inner.counter(), 1);
-        assert!(!t.inner.is_stealth());
-        assert!(!t.inner.is_destroyed());
+    fn try_clone_counted(&self) -> Option<(Self, usize)> {
+        if !self.inner().clone_allowed() {
+            return None;
+        }
 
-        let t_1 = t.clone();
-        assert_eq!(t.inner.counter(), 2);
-        assert_eq!(t_1.inner.counter(), 2);
+        // Increase counter
+        let value: usize = self.increase(1)?;
 
-        let t_2 = t_1.clone();
-        assert_eq!(t.inner.counter(), 3);
-        assert_eq!(t_1.inner.counter(), 3);
-        assert_eq!(t_2.inner.counter(), 3);
+        #[cfg(feature = "tracing")]
+        if let Some(name) = &self.log_name() {
+            tracing::trace!(
+                group_id = self.group_id(),
+                "{} cloned: atomic counter increased to {}",
+                name,
+                value
+            );
+        }
 
-        drop(t_1);
-        assert_eq!(t.inner.counter(), 2);
-        assert!(!t.inner.is_destroyed());
+        // Clone
+        let clone: Self = Self {
+            state: self.state.clone(),
+            stealth: false,
+            label: self.label,
+            generation: self.generation,
+            #[cfg(feature = "std")]
+            joined: self.joined,
+            #[cfg(feature = "thread-tracking")]
+            thread: self.state.track_thread(1),
+            inner: Some(self.inner().clone()),
+        };
+        clone.inner().on_clone(value);
 
-        drop(t_2);
-        assert_eq!(t.inner.counter(), 1);
+        Some((clone, value))
     }
 
-    #[test]
-    fn test_stealth_clone() {
-        let t = TestingStealth::new();
-        assert_eq!(t.inner.counter(), 1);
-        assert!(!t.inner.is_stealth());
+    /// Clone and name the new instance
+    ///
+    /// Like a normal clone, but with the `tracing` feature the logs of the new instance include `name`,
+    /// to tell the clones apart. Cloning the named instance preserves the name.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the [clone limit](AtomicDestructor::clone_limit) is reached.
+    #[track_caller]
+    pub fn clone_and_name(&self, name: &'static str) -> Self {
+        let mut clone: Self = self.clone();
+        clone.label = Some(name);
+        clone
+    }
 
-        let t_1 = t.stealth_clone();
-        assert_eq!(t.inner.counter(), 1);
-        assert_eq!(t_1.inner.counter(), 1);
-        assert!(!t.inner.is_stealth());
-        assert!(t_1.inner.is_stealth());
+    /// Get the instance name, set with [`AtomicDestructor::clone_and_name`]
+    pub fn clone_name(&self) -> Option<&'static str> {
+        self.label
+    }
+
+    /// Name used in logs: the inner name followed by the instance name, if any
+    #[cfg(feature = "tracing")]
+    fn log_name(&self) -> Option<String> {
+        match (self.inner().name(), self.label) {
+            (Some(name), Some(label)) => Some(format!("{} [{}]", name, label)),
+            (Some(name), None) => Some(name),
+            (None, Some(label)) => Some(String::from(label)),
+            (None, None) => None,
+        }
+    }
+
+    /// Clone `n` times, increasing the counter with a single atomic operation
+    ///
+    /// # Panics
+    ///
+    /// Panics if the [clone limit](AtomicDestructor::clone_limit) would be exceeded or if [cloning is not allowed](AtomicDestroyer::clone_allowed).
+    #[track_caller]
+    pub fn clone_n(&self, n: usize) -> Vec<Self> {
+        if n == 0 {
+            return Vec::new();
+        }
+
+        assert!(self.inner().clone_allowed(), "clone not allowed");
+
+        // Increase counter
+        let value: usize = match self.increase(n) {
+            Some(value) => value,
+            None => self.state.refuse_clone(),
+        };
+
+        #[cfg(feature = "tracing")]
+        if let Some(name) = &self.log_name() {
+            tracing::trace!(
+                group_id = self.group_id(),
+                "{} cloned {} times: atomic counter increased to {}",
+                name,
+                n,
+                value
+            );
+        }
+
+        #[cfg(feature = "thread-tracking")]
+        let thread: Option<ThreadId> = self.state.track_thread(n);
+
+        // Counter value of the first clone
+        let first: usize = value.saturating_sub(n - 1);
+
+        (0..n)
+            .map(|i| {
+                let clone: Self = Self {
+                    state: self.state.clone(),
+                    stealth: false,
+                    label: self.label,
+                    generation: self.generation,
+                    #[cfg(feature = "std")]
+                    joined: self.joined,
+                    #[cfg(feature = "thread-tracking")]
+                    thread,
+                    inner: Some(self.inner().clone()),
+                };
+                clone.inner().on_clone(first.saturating_add(i));
+                clone
+            })
+            .collect()
+    }
+
+    /// Drop all the instances, in order
+    ///
+    /// If they are the last instances, the destruction is run by the final element.
+    ///
+    /// Makes the teardown point explicit, but it's not faster than dropping the `Vec`:
+    /// every element is dropped on its own, with its own counter decrement and callbacks.
+    pub fn drop_all(items: Vec<Self>) {
+        for item in items.into_iter() {
+            drop(item);
+        }
+    }
+
+    /// Modify the inner in place
+    ///
+    /// Return an error if destroyed.
+    pub fn map_in_place<F>(&mut self, f: F) -> Result<(), DestroyedError>
+    where
+        F: FnOnce(&mut T),
+    {
+        if self.is_destroyed() {
+            return Err(DestroyedError);
+        }
+
+        f(self.inner_mut());
+        self.bump_version();
+        Ok(())
+    }
+
+    /// Modify the inner in place with a fallible function
+    ///
+    /// Return an error if destroyed or if `f` fails. In the latter case, the changes made by `f` to the inner are kept.
+    pub fn try_map_in_place<E, F>(&mut self, f: F) -> Result<(), TryMapError<E>>
+    where
+        F: FnOnce(&mut T) -> Result<(), E>,
+    {
+        if self.is_destroyed() {
+            return Err(TryMapError::Destroyed(DestroyedError));
+        }
+
+        let res: Result<(), E> = f(self.inner_mut());
+        self.bump_version();
+        res.map_err(TryMapError::Map)
+    }
+
+    /// Get a mutable reference to the inner, detaching from the other instances if not unique
+    ///
+    /// Like [`Arc::make_mut`]: if this is the only non-stealth instance (see [`AtomicDestructor::counter`]),
+    /// the inner is returned directly. Otherwise, the inner is cloned into a new tracked instance,
+    /// with its own lifecycle, that replaces `self`.
+    ///
+    /// The old shared counter is decreased as if `self` was dropped: the old lifecycle is destroyed only if
+    /// all the other instances are already gone.
+    pub fn make_mut(&mut self) -> &mut T {
+        if !self.is_unique() {
+            let mut state: State = State::new(1);
+            state.max_clones = self.state.max_clones;
+            state.trigger = self.state.trigger;
+            state.forbid_stealth_during_destroy = self.state.forbid_stealth_during_destroy;
+
+            let state: Arc<State> = Arc::new(state);
+            let detached: Self = Self {
+                #[cfg(feature = "thread-tracking")]
+                thread: state.track_thread(1),
+                state,
+                stealth: false,
+                label: self.label,
+                generation: 0,
+                #[cfg(feature = "std")]
+                joined: false,
+                inner: Some(self.inner().clone()),
+            };
+
+            #[cfg(feature = "tracing")]
+            if let Some(name) = &self.log_name() {
+                tracing::trace!(
+                    group_id = self.group_id(),
+                    "{} detached into a new lifecycle",
+                    name
+                );
+            }
+
+            // Drop the old instance (decrease the old counter)
+            drop(core::mem::replace(self, detached));
+        }
+
+        self.bump_version();
+        self.inner_mut()
+    }
+
+    /// Get the max number of non-stealth instances that can be alive at the same time
+    ///
+    /// Configured with [`AtomicDestructorBuilder::max_clones`].
+    pub fn clone_limit(&self) -> Option<usize> {
+        self.state.max_clones
+    }
+
+    /// Get when the destruction is triggered
+    ///
+    /// Configured with [`AtomicDestructorBuilder::destroy_trigger`].
+    pub fn destroy_trigger(&self) -> DestroyTrigger {
+        self.state.trigger
+    }
+
+    /// Get the inner
+    ///
+    /// Like [`Deref`], but explicit: useful when `T` has methods with the same name of the wrapper ones (i.e., `counter`).
+    /// The inner is returned even if destroyed.
+    pub fn inner(&self) -> &T {
+        self.inner.as_ref().expect("inner moved out")
+    }
+
+    /// Get a mutable reference to the inner
+    ///
+    /// Like [`DerefMut`], but explicit. See [`AtomicDestructor::inner`].
+    pub fn inner_mut(&mut self) -> &mut T {
+        self.inner.as_mut().expect("inner moved out")
+    }
+
+    /// Get a mutable reference to the inner of a pinned wrapper
+    ///
+    /// The inner is not pinned (see the [`Unpin`] impl), so it can be accessed and moved freely.
+    pub fn get_pin_mut(self: Pin<&mut Self>) -> &mut T {
+        Pin::get_mut(self).inner_mut()
+    }
+
+    /// Replace the inner, returning the old one
+    ///
+    /// No callback is called: the lifecycle is not affected. Works on pinned wrappers too (i.e., `pinned.as_mut().replace_inner(..)`).
+    pub fn replace_inner(&mut self, inner: T) -> T {
+        self.bump_version();
+        core::mem::replace(self.inner_mut(), inner)
+    }
+
+    /// Get the version of the inner, shared by all the instances
+    ///
+    /// Starts at `0` and is increased by every mutation through [`AtomicDestructor::replace_inner`], [`AtomicDestructor::map_in_place`],
+    /// [`AtomicDestructor::try_map_in_place`], [`AtomicDestructor::make_mut`] and `compare_and_swap_inner`.
+    /// Mutations through [`DerefMut`] are not tracked.
+    ///
+    /// Observers can poll it to detect stale cached copies of the inner (see [`AtomicDestructor::version_changed`]).
+    pub fn version(&self) -> u64 {
+        self.state.version.load(Ordering::SeqCst)
+    }
+
+    /// Check if the inner was mutated since the `since` [version](AtomicDestructor::version)
+    pub fn version_changed(&self, since: u64) -> bool {
+        self.version() != since
+    }
+
+    fn bump_version(&self) {
+        self.state.version.fetch_add(1, Ordering::SeqCst);
+    }
+
+    /// Get the inner, if not destroyed
+    ///
+    /// Return `None` if destroyed or [poisoned](AtomicDestructor::poison).
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use atomic_destructor::{AtomicDestructor, NoopDestroyer, StealthClone};
+    ///
+    /// let t = AtomicDestructor::new(NoopDestroyer(vec![1, 2, 3]));
+    /// let observer = t.stealth_clone();
+    /// assert_eq!(observer.peek().map(|v| v.len()), Some(3));
+    ///
+    /// drop(t);
+    /// assert_eq!(observer.peek().map(|v| v.len()), None);
+    /// ```
+    pub fn peek(&self) -> Option<&T> {
+        if self.is_destroyed() || self.is_poisoned() {
+            None
+        } else {
+            Some(self.inner())
+        }
+    }
+
+    /// Get an owned copy of the inner
+    ///
+    /// The idiomatic way to extract a standalone `T`: clearer than `(*wrapper).clone()`,
+    /// which reads like cloning the wrapper. The counter is not affected.
+    ///
+    /// # Panics
+    ///
+    /// Panics if destroyed. Use [`AtomicDestructor::try_to_owned_inner`] to handle it.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use atomic_destructor::{AtomicDestructor, NoopDestroyer};
+    ///
+    /// let t = AtomicDestructor::new(NoopDestroyer(vec![1, 2, 3]));
+    /// let inner: NoopDestroyer<Vec<i32>> = t.to_owned_inner();
+    /// assert_eq!(inner.0, vec![1, 2, 3]);
+    /// assert_eq!(t.counter(), 1);
+    /// ```
+    #[track_caller]
+    pub fn to_owned_inner(&self) -> T {
+        match self.try_to_owned_inner() {
+            Some(inner) => inner,
+            None => panic!("inner already destroyed"),
+        }
+    }
+
+    /// Get an owned copy of the inner, if not destroyed
+    ///
+    /// Non-panicking version of [`AtomicDestructor::to_owned_inner`].
+    pub fn try_to_owned_inner(&self) -> Option<T> {
+        if self.is_destroyed() {
+            None
+        } else {
+            Some(self.inner().clone())
+        }
+    }
+
+    /// Compact lifecycle display, i.e. for error messages
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use atomic_destructor::{AtomicDestructor, NoopDestroyer, StealthClone};
+    ///
+    /// let t = AtomicDestructor::new(NoopDestroyer("resource"));
+    /// let t_1 = t.clone();
+    /// let observer = t.stealth_clone();
+    /// assert_eq!(
+    ///     format!("operation failed for resource {}", t.fmt_lifecycle()),
+    ///     "operation failed for resource [alive:2]"
+    /// );
+    /// assert_eq!(observer.fmt_lifecycle().to_string(), "[alive:2s]");
+    ///
+    /// drop(t);
+    /// drop(t_1);
+    /// assert_eq!(observer.fmt_lifecycle().to_string(), "[destroyed]");
+    /// ```
+    pub fn fmt_lifecycle(&self) -> LifecycleDisplay<'_, T> {
+        LifecycleDisplay::new(self)
+    }
+
+    /// Borrow as a lifetime-scoped view, without touching the counter
+    pub fn as_borrowed(&self) -> BorrowedAtomicDestructor<'_, T> {
+        BorrowedAtomicDestructor::from(self)
+    }
+
+    /// Convert into a [`DestructorManager`]
+    pub fn into_managed(self) -> DestructorManager<T> {
+        DestructorManager::new(self)
+    }
+
+    /// Get an observer that doesn't keep the destructor alive, and can't access the inner once destroyed
+    pub fn as_observer_weak(&self) -> ObserverWeak<T> {
+        ObserverWeak::new(self)
+    }
+
+    /// Get the shared lifecycle state
+    ///
+    /// A plain `Arc` handle, without the generic `T`: check the liveness with [`is_destroyed_of`].
+    /// Holding it doesn't keep the lifecycle alive.
+    pub fn state(&self) -> Arc<State> {
+        self.state.clone()
+    }
+
+    /// New instance sharing the same state, with a different inner
+    ///
+    /// The new instance has the same stealth mode and is counted like a clone.
+    /// Return `None` if the clone is refused (i.e., [clone limit](AtomicDestructor::clone_limit) reached).
+    #[cfg(feature = "tokio")]
+    fn map_view<U>(&self, inner: U) -> Option<AtomicDestructor<U>>
+    where
+        U: AtomicDestroyer,
+    {
+        #[cfg(feature = "thread-tracking")]
+        let mut thread: Option<ThreadId> = None;
+
+        if self.stealth {
+            // Increase stealth counter
+            self.state
+                .stealth_counter
+                .saturating_increment(Ordering::SeqCst);
+        } else {
+            // Increase counter
+            self.increase(1)?;
+
+            #[cfg(feature = "thread-tracking")]
+            {
+                thread = self.state.track_thread(1);
+            }
+        }
+
+        Some(AtomicDestructor {
+            state: self.state.clone(),
+            stealth: self.stealth,
+            label: self.label,
+            generation: self.generation,
+            #[cfg(feature = "std")]
+            joined: self.joined,
+            #[cfg(feature = "thread-tracking")]
+            thread,
+            inner: Some(inner),
+        })
+    }
+
+    /// Split into a stealth handle, to keep for observation, and the counted instance, to send to another thread
+    ///
+    /// Return `(stealth_for_sender, counted_for_thread)`. The counter is not changed:
+    /// the lifecycle is now held only by `counted_for_thread`.
+    ///
+    /// If `self` is stealth, both the returned instances are stealth.
+    pub fn transfer_to_thread(self) -> (Self, Self) {
+        (self.stealth_clone(), self)
+    }
+
+    /// Return `self`, checking at compile time that it's `'static`
+    ///
+    /// Zero-cost: useful to get bound errors at the call site instead of deep inside a spawn (i.e., `tokio::spawn`).
+    pub fn into_static(self) -> Self
+    where
+        T: 'static,
+    {
+        self
+    }
+
+    /// Return `self`, checking at compile time that it can be sent to and shared with other threads
+    ///
+    /// Zero-cost, like [`AtomicDestructor::into_static`].
+    pub fn into_send(self) -> Self
+    where
+        T: Send + Sync,
+    {
+        self
+    }
+
+    /// Swap the internals of two wrappers
+    ///
+    /// Both the lifecycle state and the inner are exchanged, without triggering any callback.
+    pub fn swap(this: &mut Self, other: &mut Self) {
+        core::mem::swap(this, other);
+    }
+
+    /// Start a new generation of the destroyed lifecycle, to reuse it
+    ///
+    /// `self` becomes the only non-stealth instance of the new generation, and the lifecycle can be destroyed again.
+    /// The instances of the previous generations are stale: they are still destroyed,
+    /// and dropping them late doesn't decrease the new counter nor run a cleanup on the new generation.
+    /// Their clones are stale too.
+    ///
+    /// The listeners and the dependencies are not restored.
+    ///
+    /// Return an error if the current generation isn't destroyed or if [poisoned](AtomicDestructor::poison):
+    /// like a `Mutex`, the poisoning must be acknowledged with [`AtomicDestructor::clear_poison`] first.
+    pub fn reset(&mut self) -> Result<(), ResetError> {
+        if self.is_poisoned() {
+            return Err(ResetError::Poisoned);
+        }
+
+        if self
+            .state
+            .destroyed
+            .compare_exchange(true, false, Ordering::SeqCst, Ordering::SeqCst)
+            .is_err()
+        {
+            return Err(ResetError::NotDestroyed);
+        }
+
+        if self.stealth {
+            // Become the counted instance
+            self.state
+                .stealth_counter
+                .saturating_decrement(Ordering::SeqCst);
+            self.stealth = false;
+
+            #[cfg(feature = "thread-tracking")]
+            {
+                self.thread = self.state.track_thread(1);
+            }
+        }
+
+        self.state.counter.store(1, Ordering::SeqCst);
+        self.state.pending.store(false, Ordering::SeqCst);
+
+        #[cfg(feature = "std")]
+        {
+            *sync::lock(&self.state.pre_listeners) = Some(Vec::new());
+            *sync::lock(&self.state.post_listeners) = Some(Vec::new());
+            #[cfg(feature = "gc")]
+            {
+                *sync::lock(&self.state.gc_hooks) = Some(Vec::new());
+            }
+            *sync::lock(&self.state.destroy_duration) = None;
+            *sync::lock(&self.state.destroy_error) = None;
+            #[cfg(feature = "timestamps")]
+            {
+                *sync::lock(&self.state.destroyed_at) = None;
+            }
+        }
+
+        self.generation = self.state.generation.fetch_add(1, Ordering::SeqCst) + 1;
+
+        // Allow a new destruction
+        self.state.destroying.store(false, Ordering::SeqCst);
+
+        #[cfg(feature = "tracing")]
+        if let Some(name) = &self.log_name() {
+            tracing::trace!(
+                group_id = self.group_id(),
+                "{} reset to generation {}",
+                name,
+                self.generation
+            );
+        }
+
+        Ok(())
+    }
+
+    /// Destroy only if the counter is equal to `expected`
+    ///
+    /// The check and the transition to destroyed are done atomically,
+    /// so a racing clone/drop can't slip between them.
+    ///
+    /// If a [`DestroyGuard`] is held, the destruction is deferred to its drop.
+    ///
+    /// Return `Err` with the actual counter if it doesn't match.
+    /// An instance of a previous generation (see [`AtomicDestructor::reset`]) always gets `Err(0)`:
+    /// its lifecycle is already destroyed.
+    pub fn compare_and_destroy(&self, expected: usize) -> Result<(), usize> {
+        // A previous generation can't touch the counter of the current one
+        if self.is_stale() {
+            return Err(0);
+        }
+
+        self.state
+            .counter
+            .compare_exchange(expected, 0, Ordering::SeqCst, Ordering::SeqCst)?;
+        self.destroy_or_defer();
+        Ok(())
+    }
+
+    /// Destroy now, regardless of the counter and of the held [`DestroyGuard`]s
+    ///
+    /// The remaining instances are marked as destroyed
+    /// and [`AtomicDestroyer::on_destroy`] is not called again when they are dropped.
+    ///
+    /// Return `true` if this call ran the destruction, `false` if already destroyed.
+    pub fn try_destroy(&self) -> bool {
+        self.destroy()
+    }
+
+    /// Destroy and get back the inner, if this is the only non-stealth instance
+    ///
+    /// Run [`AtomicDestroyer::on_destroy`] now and move the inner out, i.e. to inspect its final state after the cleanup.
+    /// Dropping the wrapper doesn't call [`AtomicDestroyer::on_destroy`] again.
+    ///
+    /// The uniqueness check and the transition to destroyed are done atomically, like [`AtomicDestructor::compare_and_destroy`].
+    ///
+    /// Return `Err` with `self` if not unique (or stealth), without destroying.
+    pub fn destroy_and_into_inner(mut self) -> Result<T, Self> {
+        if !self.destroy_if_unique() {
+            return Err(self);
+        }
+
+        // Drop of `self` sees it as destroyed: no second destruction
+        Ok(self.take_inner())
+    }
+
+    /// Destroy now, if this is the only non-stealth instance
+    ///
+    /// For the last holder that wants to clean up eagerly, instead of at the end of the scope.
+    /// Like [`AtomicDestructor::destroy_and_into_inner`], without getting back the inner.
+    ///
+    /// Return `Err` with `self` if not unique (or stealth) or already destroyed, without destroying.
+    pub fn try_destroy_now(self) -> Result<(), Self> {
+        if self.destroy_if_unique() {
+            Ok(())
+        } else {
+            Err(self)
+        }
+    }
+
+    /// Atomically bring the counter from `1` to `0` and destroy
+    ///
+    /// Return `false` if stealth, destroyed (or stale) or not unique.
+    fn destroy_if_unique(&self) -> bool {
+        if self.is_stealth() || self.is_destroyed() {
+            return false;
+        }
+
+        if self
+            .state
+            .counter
+            .compare_exchange(1, 0, Ordering::SeqCst, Ordering::SeqCst)
+            .is_err()
+        {
+            return false;
+        }
+
+        self.destroy()
+    }
+
+    /// Reclaim the inner without destroying it, if this is the only non-stealth instance
+    ///
+    /// The lifecycle is marked as destroyed, but [`AtomicDestroyer::on_destroy`] is **not** called:
+    /// the caller takes over the resource and its cleanup. The listeners and the waiters are notified like for a regular destruction.
+    ///
+    /// The inner is moved out, not cloned.
+    ///
+    /// Return `None` if not unique (or stealth) or already destroyed.
+    pub fn transfer_inner(mut self) -> Option<T> {
+        if self.is_stealth() || self.is_destroyed() {
+            return None;
+        }
+
+        self.state
+            .counter
+            .compare_exchange(1, 0, Ordering::SeqCst, Ordering::SeqCst)
+            .ok()?;
+
+        // Claim the destruction, without running it
+        self.state
+            .destroying
+            .compare_exchange(false, true, Ordering::SeqCst, Ordering::SeqCst)
+            .ok()?;
+
+        self.state.destroyed.store(true, Ordering::SeqCst);
+
+        #[cfg(feature = "timestamps")]
+        {
+            *sync::lock(&self.state.destroyed_at) = Some(Instant::now());
+        }
+
+        #[cfg(feature = "tracing")]
+        if let Some(name) = &self.log_name() {
+            tracing::trace!(group_id = self.group_id(), "{} inner transferred.", name);
+        }
+
+        #[cfg(feature = "std")]
+        {
+            State::notify(&self.state.pre_listeners);
+            State::notify(&self.state.post_listeners);
+            self.state.release_dependencies();
+            self.state.notify_drop();
+        }
+
+        Some(self.take_inner())
+    }
+
+    /// Move the inner out: the wrapper must be already destroyed, since it's dropped without it
+    fn take_inner(&mut self) -> T {
+        self.inner.take().expect("inner moved out")
+    }
+
+    /// Keep `dependency` alive until this destructor is destroyed
+    ///
+    /// A clone of `dependency` is stored in the shared state and dropped right after [`AtomicDestroyer::on_destroy`],
+    /// so `dependency` is always destroyed after `self`.
+    ///
+    /// Don't create cycles: destructors that depend on each other are never destroyed.
+    #[cfg(feature = "std")]
+    pub fn destroys_after<U>(&self, dependency: &AtomicDestructor<U>)
+    where
+        U: AtomicDestroyer + Send + Sync + 'static,
+    {
+        let dependency: Box<dyn Any + Send + Sync> = Box::new(dependency.clone());
+        sync::lock(&self.state.dependencies).push(dependency);
+    }
+
+    /// Make `T` the lifecycle owner, if there isn't one yet
+    ///
+    /// The owner is destroyed instead of the inner of the last dropped instance, that may have another type.
+    #[cfg(feature = "std")]
+    fn set_owner(&self)
+    where
+        T: Send + Sync + 'static,
+    {
+        let mut owner = sync::lock(&self.state.owner);
+        if owner.is_none() {
+            *owner = Some(DynDestroyer::new(self.inner().clone()));
+        }
+    }
+
+    /// Get a type-erased liveness handle, sharing this lifecycle
+    ///
+    /// Useful to store the handles of different inner types together (i.e., `Vec<AtomicDestructor<Heartbeat>>`).
+    /// Unless `stealth`, the heartbeat is counted like a clone and keeps the lifecycle alive:
+    /// if it's the last dropped instance, `T` is still destroyed, using a copy of the inner taken by the first heartbeat.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the [clone limit](AtomicDestructor::clone_limit) is reached.
+    #[cfg(feature = "std")]
+    #[track_caller]
+    pub fn heartbeat(&self, stealth: bool) -> AtomicDestructor<Heartbeat>
+    where
+        T: Send + Sync + 'static,
+    {
+        if !stealth {
+            self.set_owner();
+        }
+
+        let mut heartbeat: AtomicDestructor<Heartbeat> =
+            AtomicDestructor::from_parts(Heartbeat(()), self.state(), stealth);
+        heartbeat.label = self.label;
+        heartbeat.joined = true;
+        heartbeat
+    }
+
+    /// Move `sibling` into this lifecycle
+    ///
+    /// `sibling` is replaced by an instance of the same inner sharing this state and counted like a clone (same stealth mode).
+    /// Its old instance is kept alive like a [dependency](AtomicDestructor::destroys_after) and released after the destruction,
+    /// so when the combined counter reaches zero both [`AtomicDestroyer::on_destroy`] are called, whatever the last dropped instance:
+    /// `T` first, then `U` (unless other instances of the old `sibling` lifecycle are still alive).
+    ///
+    /// Do nothing if they already share the same lifecycle.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the [clone limit](AtomicDestructor::clone_limit) is reached.
+    #[cfg(feature = "std")]
+    #[track_caller]
+    pub fn add_sibling<U>(&mut self, sibling: &mut AtomicDestructor<U>)
+    where
+        T: Send + Sync + 'static,
+        U: AtomicDestroyer + Send + Sync + 'static,
+    {
+        if shares_state(self, sibling) {
+            return;
+        }
+
+        // Destroy `T` even if the last dropped instance is a sibling
+        self.set_owner();
+
+        let mut joined: AtomicDestructor<U> =
+            AtomicDestructor::from_parts(sibling.inner().clone(), self.state(), sibling.stealth);
+        joined.label = sibling.label;
+        joined.joined = true;
+
+        let old: AtomicDestructor<U> = core::mem::replace(sibling, joined);
+        let old: Box<dyn Any + Send + Sync> = Box::new(old);
+        sync::lock(&self.state.dependencies).push(old);
+    }
+
+    /// Subscribe a listener executed right **before** [`AtomicDestroyer::on_destroy`]
+    ///
+    /// If already destroyed, the listener is called immediately.
+    #[cfg(feature = "std")]
+    pub fn on_destroy_subscribe_pre<F>(&self, listener: F)
+    where
+        F: FnOnce() + Send + 'static,
+    {
+        State::subscribe(&self.state.pre_listeners, Box::new(listener));
+    }
+
+    /// Register a listener executed every time a stealth instance of this lifecycle is dropped
+    ///
+    /// Includes the stealth instances held internally (i.e., by [`DestroyGuard`] and [`ObserverWeak`]).
+    #[cfg(feature = "std")]
+    pub fn on_stealth_drop<F>(&self, listener: F)
+    where
+        F: Fn() + Send + Sync + 'static,
+    {
+        sync::lock(&self.state.stealth_drop_listeners).push(Arc::new(listener));
+        self.state
+            .has_stealth_drop_listeners
+            .store(true, Ordering::SeqCst);
+    }
+
+    /// Subscribe a listener executed right **after** [`AtomicDestroyer::on_destroy`]
+    ///
+    /// If already destroyed, the listener is called immediately.
+    #[cfg(feature = "std")]
+    pub fn on_destroy_subscribe_post<F>(&self, listener: F)
+    where
+        F: FnOnce() + Send + 'static,
+    {
+        State::subscribe(&self.state.post_listeners, Box::new(listener));
+    }
+
+    /// Block until this is the only non-stealth instance, or until destroyed
+    ///
+    /// For resource managers taking back the exclusive ownership of a pooled resource.
+    #[cfg_attr(
+        feature = "async",
+        doc = "See [`AtomicDestructor::shrink_to_last`] for the async version."
+    )]
+    ///
+    /// # Panics
+    ///
+    /// Panics if stealth: it can never be the last instance.
+    #[track_caller]
+    #[cfg(feature = "std")]
+    pub fn shrink_to_last_blocking(&self) {
+        assert!(!self.is_stealth(), "stealth instance can't be the last one");
+
+        let mut wakers = sync::lock(&self.state.drop_wakers);
+        loop {
+            self.state.expect_drop();
+            if self.is_unique() || self.is_destroyed() {
+                return;
+            }
+
+            wakers = sync::wait(&self.state.dropped, wakers);
+        }
+    }
+
+    /// Release the unused capacity of the listeners storage
+    ///
+    /// For long-lived lifecycles with bursts of subscriptions: the storage retains the capacity of the largest burst.
+    #[cfg(feature = "std")]
+    pub fn shrink_listeners(&self) {
+        self.state.shrink_listeners();
+    }
+
+    /// Simulate `n` simultaneous drops of non-stealth instances, for stress tests
+    ///
+    /// The counter is decreased by `n` with a single atomic operation, without dropping any instance:
+    /// if it reaches zero, the destruction is run (once). The real instances are already destroyed when dropped later.
+    #[cfg(feature = "testing")]
+    pub fn simulate_concurrent_drops(&self, n: usize) {
+        if n == 0 || self.is_destroyed() {
+            return;
+        }
+
+        // Decrease counter
+        let value: usize = self.state.counter.saturating_sub(n, Ordering::SeqCst);
+
+        #[cfg(feature = "tracing")]
+        if let Some(name) = &self.log_name() {
+            tracing::trace!(
+                group_id = self.group_id(),
+                "{} simulated {} drops: atomic counter decreased to {}",
+                name,
+                n,
+                value
+            );
+        }
+
+        // Check if it's time for destruction
+        if value == 0 || self.state.trigger == DestroyTrigger::FirstDrop || self.is_poisoned() {
+            self.destroy_or_defer();
+        }
+    }
+
+    /// Acquire a guard that defers the destruction until it's dropped
+    ///
+    /// The guard doesn't increase the counter: if the last instance is dropped while the guard is held,
+    /// the destruction is executed when the guard is dropped.
+    pub fn destroy_guard(&self) -> DestroyGuard<T> {
+        DestroyGuard::new(self.stealth_clone())
+    }
+
+    /// The counter reached zero: destroy, unless a guard is held.
+    fn destroy_or_defer(&self) {
+        // Mark as pending BEFORE checking the guards, so a racing guard drop can't miss it
+        self.state.pending.store(true, Ordering::SeqCst);
+
+        if self.state.guards.load(Ordering::SeqCst) == 0 {
+            self.destroy();
+        } else {
+            #[cfg(feature = "tracing")]
+            if let Some(name) = &self.log_name() {
+                tracing::trace!(
+                    group_id = self.group_id(),
+                    "{} destruction deferred: guard held.",
+                    name
+                );
+            }
+        }
+    }
+
+    /// Run destruction, if not already done.
+    ///
+    /// More threads may get here at the same time: i.e., two racing [`AtomicDestructor::try_destroy`],
+    /// or two drops both observing a zero counter because of a logic error (the counter saturates at `0`).
+    /// The destruction is claimed with a `compare_exchange` on the `destroying` flag:
+    /// only the thread that wins it runs [`AtomicDestroyer::on_destroy`], the others skip the cleanup and return immediately,
+    /// without waiting for it to complete.
+    ///
+    /// The destroyed flag is set right after the claim, **before** calling [`AtomicDestroyer::on_destroy`].
+    ///
+    /// Return `true` if this call ran the destruction.
+    fn destroy(&self) -> bool {
+        // A previous generation can't destroy the current one
+        if self.is_stale() {
+            return false;
+        }
+
+        // Claim the destruction
+        if self
+            .state
+            .destroying
+            .compare_exchange(false, true, Ordering::SeqCst, Ordering::SeqCst)
+            .is_err()
+        {
+            #[cfg(feature = "tracing")]
+            if let Some(name) = &self.log_name() {
+                tracing::trace!(group_id = self.group_id(), "{} already destroyed.", name);
+            }
+
+            return false;
+        }
+
+        // Mark as destroyed
+        self.state.destroyed.store(true, Ordering::SeqCst);
+
+        #[cfg(feature = "timestamps")]
+        {
+            *sync::lock(&self.state.destroyed_at) = Some(Instant::now());
+        }
+
+        #[cfg(feature = "stats")]
+        if let Some(stats) = &self.state.stats {
+            stats.record_destroy();
+        }
+
+        #[cfg(feature = "tracing")]
+        if let Some(name) = &self.log_name() {
+            tracing::trace!(group_id = self.group_id(), "Destroying {} ...", name);
+        }
+
+        #[cfg(feature = "std")]
+        State::notify(&self.state.pre_listeners);
+
+        // Destroy
+        #[cfg(feature = "std")]
+        let start: Instant = Instant::now();
+
+        #[cfg(feature = "std")]
+        let poison_on_panic = PoisonOnPanic(&self.state);
+
+        self.destroy_inner();
+
+        #[cfg(feature = "std")]
+        drop(poison_on_panic);
+
+        #[cfg(feature = "std")]
+        {
+            let elapsed: Duration = start.elapsed();
+            *sync::lock(&self.state.destroy_duration) = Some(elapsed);
+
+            #[cfg(feature = "tracing")]
+            if let Some(name) = &self.log_name() {
+                tracing::trace!(
+                    group_id = self.group_id(),
+                    "{} on_destroy took {:?}",
+                    name,
+                    elapsed
+                );
+            }
+        }
+
+        #[cfg(feature = "std")]
+        self.state.finish_destroy();
+
+        #[cfg(feature = "tracing")]
+        if let Some(name) = &self.log_name() {
+            tracing::trace!(group_id = self.group_id(), "{} destroyed", name);
+        }
+
+        if self.state.forbid_stealth_during_destroy {
+            self.check_no_stealth();
+        }
+
+        true
+    }
+
+    /// Report the stealth instances, other than `self`, still alive
+    ///
+    /// Usually called from [`Drop`]: panics only in debug builds.
+    fn check_no_stealth(&self) {
+        let mut observers: usize = self.stealth_count();
+        if self.is_stealth() {
+            observers = observers.saturating_sub(1);
+        }
+
+        #[cfg(feature = "tracing")]
+        if observers > 0 {
+            tracing::error!(
+                group_id = self.group_id(),
+                "Destroyed while {} stealth instances are alive",
+                observers
+            );
+        }
+
+        debug_assert!(
+            observers == 0,
+            "destroyed while {} stealth instances are alive",
+            observers
+        );
+    }
+
+    /// Get the ID shared by all the instances of this lifecycle
+    ///
+    /// Assigned when the lifecycle is created (i.e., [`AtomicDestructor::new`]) and inherited by all the clones,
+    /// stealth included. With the `tracing` feature, it's attached to every log as the `group_id` field.
+    ///
+    /// IDs are process-scoped and monotonic: they are not meaningful across restarts.
+    pub fn group_id(&self) -> u64 {
+        self.state.group_id
+    }
+
+    /// Destroy the lifecycle owner, if any and joined, otherwise the inner
+    fn destroy_inner(&self) {
+        #[cfg(feature = "std")]
+        {
+            let owner: Option<DynDestroyer> = sync::lock(&self.state.owner).take();
+            if let (Some(owner), true) = (owner, self.joined) {
+                self.destroy_with(&owner);
+                return;
+            }
+        }
+
+        self.destroy_with(self.inner());
+    }
+
+    /// Call the destruction hook of `inner`, recording the error if it fails
+    fn destroy_with<D>(&self, inner: &D)
+    where
+        D: AtomicDestroyer,
+    {
+        let stats: DestroyStats = self.state.destroy_stats();
+
+        #[cfg(feature = "std")]
+        {
+            if let Err(e) = call_destroy_hook(inner, stats) {
+                #[cfg(feature = "tracing")]
+                if let Some(name) = &self.log_name() {
+                    tracing::warn!(
+                        group_id = self.group_id(),
+                        "{} destruction failed: {}",
+                        name,
+                        e
+                    );
+                }
+
+                *sync::lock(&self.state.destroy_error) = Some(DestroyError::from(e));
+            }
+        }
+
+        #[cfg(not(feature = "std"))]
+        inner.on_destroy_with_stats(stats);
+    }
+
+    /// Get the error returned by [`AtomicDestroyer::on_destroy_fallible`]
+    ///
+    /// Return `None` if not destroyed yet or if the destruction succeeded.
+    #[cfg(feature = "std")]
+    pub fn last_destroy_error(&self) -> Option<DestroyError> {
+        sync::lock(&self.state.destroy_error).clone()
+    }
+
+    /// Get how long [`AtomicDestroyer::on_destroy`] took
+    ///
+    /// Useful to find slow teardowns. Return `None` if not destroyed yet (or if the destruction is still running).
+    #[cfg(feature = "std")]
+    pub fn last_destroy_duration(&self) -> Option<Duration> {
+        *sync::lock(&self.state.destroy_duration)
+    }
+
+    /// Get when the lifecycle was first created
+    ///
+    /// Shared by all the instances, and not changed by [`AtomicDestructor::reset`].
+    #[cfg(feature = "timestamps")]
+    pub fn created_at(&self) -> Instant {
+        self.state.created_at
+    }
+
+    /// Get when the lifecycle was marked as destroyed
+    ///
+    /// Return `None` if not destroyed yet (or if [reset](AtomicDestructor::reset) since).
+    #[cfg(feature = "timestamps")]
+    pub fn destroyed_at(&self) -> Option<Instant> {
+        *sync::lock(&self.state.destroyed_at)
+    }
+
+    /// Get counter
+    ///
+    /// The counter and the destroyed flag are separate atomics: the counter reaches `0` **before** the flag is set,
+    /// so reading them with two calls may observe an intermediate pair. Use [`AtomicDestructor::load_consistent`]
+    /// to read both.
+    ///
+    /// To get the counter produced by a clone, use [`AtomicDestructor::counted_clone`].
+    #[inline]
+    pub fn counter(&self) -> usize {
+        self.state.counter.load(Ordering::SeqCst)
+    }
+
+    /// Get counter, or `0` if destroyed
+    ///
+    /// A single coherent number: the raw [counter](AtomicDestructor::counter) may be non-zero while destroyed
+    /// (i.e., [`DestroyTrigger::FirstDrop`], or clones created by [`AtomicDestroyer::on_destroy`]).
+    pub fn effective_counter(&self) -> usize {
+        let counter: usize = self.counter();
+
+        if self.is_destroyed() {
+            0
+        } else {
+            counter
+        }
+    }
+
+    /// Get counter and destroyed flag as a stable pair
+    ///
+    /// Read the counter, then the flag, and re-read the counter, retrying until it doesn't change in between.
+    /// A stable pair with the flag set always has a counter of `0`, unless instances are created
+    /// while the destruction is being claimed.
+    pub fn load_consistent(&self) -> (usize, bool) {
+        loop {
+            let counter: usize = self.state.counter.load(Ordering::Acquire);
+            let destroyed: bool = self.state.destroyed.load(Ordering::Acquire);
+
+            if self.state.counter.load(Ordering::Acquire) == counter {
+                return (counter, destroyed);
+            }
+        }
+    }
+
+    /// Get the number of stealth instances alive
+    pub fn stealth_count(&self) -> usize {
+        self.state.stealth_counter.load(Ordering::SeqCst)
+    }
+
+    /// Check if any stealth instance is alive (i.e., an observer), this one included
+    ///
+    /// Useful before reusing the inner.
+    pub fn has_stealth_observers(&self) -> bool {
+        self.stealth_count() > 0
+    }
+
+    /// Get the number of all the instances alive, stealth included
+    pub fn total_count(&self) -> usize {
+        let (normal, stealth) = self.counts();
+        normal.saturating_add(stealth)
+    }
+
+    /// Get the number of non-stealth and stealth instances alive: `(normal, stealth)`
+    pub fn counts(&self) -> (usize, usize) {
+        (self.counter(), self.stealth_count())
+    }
+
+    /// Get the number of non-stealth instances per thread that created them
+    ///
+    /// Useful to find leaked clones. Instances are attributed to the creating thread, even if moved to another one.
+    #[cfg(feature = "thread-tracking")]
+    pub fn counts_by_thread(&self) -> HashMap<ThreadId, usize> {
+        sync::lock(&self.state.threads).clone()
+    }
+
+    /// Check if the counter has ever reached `usize::MAX`
+    ///
+    /// Once saturated, further clones no longer increase the counter, so it may reach zero while instances are still alive.
+    /// This usually indicates a leak.
+    pub fn counter_saturated(&self) -> bool {
+        self.state.saturated.load(Ordering::SeqCst)
+    }
+
+    /// Mark all the instances as poisoned
+    ///
+    /// Like a poisoned `Mutex`, for resources left in a corrupted state (i.e., a partially committed transaction):
+    /// [`AtomicDestructor::peek`] and [`AtomicDestructor::clone_if_alive`] return `None`,
+    /// and the next drop of a non-stealth instance runs the destruction, regardless of the counter.
+    ///
+    /// A panic in [`AtomicDestroyer::on_destroy`] poisons too, since the cleanup may have partially run.
+    /// A poisoned lifecycle can't be [reset](AtomicDestructor::reset) until [`AtomicDestructor::clear_poison`] is called.
+    pub fn poison(&self) {
+        self.state.poisoned.store(true, Ordering::SeqCst);
+
+        #[cfg(feature = "tracing")]
+        if let Some(name) = &self.log_name() {
+            tracing::warn!(group_id = self.group_id(), "{} poisoned", name);
+        }
+    }
+
+    /// Check if [poisoned](AtomicDestructor::poison)
+    pub fn is_poisoned(&self) -> bool {
+        self.state.poisoned.load(Ordering::SeqCst)
+    }
+
+    /// Clear the [poisoning](AtomicDestructor::poison)
+    pub fn clear_poison(&self) {
+        self.state.poisoned.store(false, Ordering::SeqCst);
+    }
+
+    /// Check if destroyed
+    ///
+    /// Instances of a previous generation (see [`AtomicDestructor::reset`]) are always destroyed.
+    ///
+    /// See [`AtomicDestructor::counter`] for the ordering with the counter.
+    #[inline]
+    pub fn is_destroyed(&self) -> bool {
+        self.is_stale() || self.state.destroyed.load(Ordering::SeqCst)
+    }
+
+    /// Check if the instance belongs to a previous generation of the lifecycle
+    fn is_stale(&self) -> bool {
+        self.generation != self.state.generation.load(Ordering::SeqCst)
+    }
+
+    /// Get the generation of the lifecycle this instance belongs to
+    ///
+    /// Starts from `0` and is increased on every [`AtomicDestructor::reset`].
+    pub fn generation(&self) -> usize {
+        self.generation
+    }
+
+    /// Check if is stealth (stealth cloned, not subject to counter increase/decrease)
+    #[inline]
+    pub fn is_stealth(&self) -> bool {
+        self.stealth
+    }
+
+    /// Check if this is the only non-stealth instance
+    ///
+    /// The result is a snapshot, possibly outdated by concurrent clones/drops.
+    #[inline]
+    pub fn is_unique(&self) -> bool {
+        !self.stealth && self.counter() == 1
+    }
+
+    /// Get a raw state snapshot, for downstream test harnesses
+    ///
+    /// **Unstable**: not part of the public API, may change or be removed in any release.
+    #[doc(hidden)]
+    pub fn __state(&self) -> RawState {
+        RawState {
+            counter: self.counter(),
+            destroyed: self.is_destroyed(),
+            stealth: self.is_stealth(),
+        }
+    }
+}
+
+/// Call [`AtomicDestroyer::on_destroy_fallible`], aborting if it panics and [`AtomicDestroyer::abort_on_destroy_panic`]
+#[cfg(feature = "std")]
+pub(crate) fn call_destroy_hook<D>(
+    inner: &D,
+    stats: DestroyStats,
+) -> Result<(), Box<dyn std::error::Error + Send + Sync>>
+where
+    D: AtomicDestroyer,
+{
+    if !inner.abort_on_destroy_panic() {
+        return inner.on_destroy_fallible(stats);
+    }
+
+    match panic::catch_unwind(AssertUnwindSafe(|| inner.on_destroy_fallible(stats))) {
+        Ok(res) => res,
+        Err(_) => {
+            #[cfg(feature = "tracing")]
+            tracing::error!("Destruction panicked, aborting.");
+
+            process::abort();
+        }
+    }
+}
+
+/// Destroy `inner` outside of the destructor (i.e., deferred by a wrapper)
+///
+/// Same hook selection of the destructor, but the error can't be stored anymore: it's only logged.
+#[cfg(any(feature = "std", feature = "tokio"))]
+pub(crate) fn destroy_detached<D>(inner: &D, stats: DestroyStats)
+where
+    D: AtomicDestroyer,
+{
+    #[cfg(feature = "std")]
+    if let Err(_e) = call_destroy_hook(inner, stats) {
+        #[cfg(feature = "tracing")]
+        match inner.name() {
+            Some(name) => tracing::warn!("{} deferred destruction failed: {}", name, _e),
+            None => tracing::warn!("Deferred destruction failed: {}", _e),
+        }
+    }
+
+    #[cfg(not(feature = "std"))]
+    inner.on_destroy_with_stats(stats);
+}
+
+/// Check if the lifecycle of a [state](AtomicDestructor::state) handle is destroyed
+///
+/// Refers to the current generation (see [`AtomicDestructor::reset`]).
+pub fn is_destroyed_of(state: &Arc<State>) -> bool {
+    state.destroyed.load(Ordering::SeqCst)
+}
+
+/// Check if two destructors, even with different inner types, share the same lifecycle state
+///
+/// I.e., a view created with [`AtomicDestructor::from_parts`] and its origin.
+pub fn shares_state<A, B>(a: &AtomicDestructor<A>, b: &AtomicDestructor<B>) -> bool
+where
+    A: AtomicDestroyer,
+    B: AtomicDestroyer,
+{
+    Arc::ptr_eq(&a.state, &b.state)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::rc::Rc;
+
+    use static_assertions::{assert_impl_all, assert_not_impl_any};
+
+    use super::*;
+    use crate::testing::CountingDestroyer;
+
+    #[derive(Debug, Clone)]
+    struct InternalTestingStealth;
+
+    #[derive(Debug, Clone)]
+    struct InternalTestingNonSend {
+        _rc: Rc<()>,
+    }
+
+    impl AtomicDestroyer for InternalTestingNonSend {
+        fn on_destroy(&self) {}
+    }
+
+    assert_impl_all!(AtomicDestructor<InternalTestingStealth>: Send, Sync);
+    assert_not_impl_any!(AtomicDestructor<InternalTestingNonSend>: Send, Sync);
+
+    #[derive(Debug, Clone)]
+    struct InternalTestingPinned {
+        value: usize,
+        _pinned: core::marker::PhantomPinned,
+    }
+
+    impl AtomicDestroyer for InternalTestingPinned {
+        fn on_destroy(&self) {}
+    }
+
+    assert_not_impl_any!(InternalTestingPinned: Unpin);
+    assert_impl_all!(AtomicDestructor<InternalTestingPinned>: Unpin);
+
+    impl AtomicDestroyer for InternalTestingStealth {
+        fn on_destroy(&self) {}
+    }
+
+    #[derive(Clone)]
+    struct TestingStealth {
+        inner: AtomicDestructor<InternalTestingStealth>,
+    }
+
+    impl StealthClone for TestingStealth {
+        fn stealth_clone(&self) -> Self {
+            Self {
+                inner: self.inner.stealth_clone(),
+            }
+        }
+    }
+
+    impl TestingStealth {
+        pub fn new() -> Self {
+            Self {
+                inner: AtomicDestructor::new(InternalTestingStealth),
+            }
+        }
+    }
+
+    #[test]
+    fn test_clone() {
+        let t = TestingStealth::new();
+        assert_eq!(t.inner.counter(), 1);
+        assert!(!t.inner.is_stealth());
+        assert!(!t.inner.is_destroyed());
+
+        let t_1 = t.clone();
+        assert_eq!(t.inner.counter(), 2);
+        assert_eq!(t_1.inner.counter(), 2);
+
+        let t_2 = t_1.clone();
+        assert_eq!(t.inner.counter(), 3);
+        assert_eq!(t_1.inner.counter(), 3);
+        assert_eq!(t_2.inner.counter(), 3);
+
+        drop(t_1);
+        assert_eq!(t.inner.counter(), 2);
+        assert!(!t.inner.is_destroyed());
+
+        drop(t_2);
+        assert_eq!(t.inner.counter(), 1);
+    }
+
+    #[test]
+    fn test_stealth_clone() {
+        let t = TestingStealth::new();
+        assert_eq!(t.inner.counter(), 1);
+        assert!(!t.inner.is_stealth());
+
+        let t_1 = t.stealth_clone();
+        assert_eq!(t.inner.counter(), 1);
+        assert_eq!(t_1.inner.counter(), 1);
+        assert!(!t.inner.is_stealth());
+        assert!(t_1.inner.is_stealth());
+
+        let t_2 = t_1.clone(); // Cloning stealth destructor
+        assert_eq!(t.inner.counter(), 2);
+        assert_eq!(t_1.inner.counter(), 2);
+        assert_eq!(t_2.inner.counter(), 2);
+
+        let t_3 = t.clone(); // Cloning NON-stealth destructor
+        assert_eq!(t.inner.counter(), 3);
+        assert_eq!(t_1.inner.counter(), 3);
+        assert_eq!(t_2.inner.counter(), 3);
+
+        drop(t_1); // Stealth
+        assert_eq!(t.inner.counter(), 3);
+
+        drop(t_2); // Classical
+        assert_eq!(t.inner.counter(), 2);
+
+        drop(t_3); // Classical
+        assert_eq!(t.inner.counter(), 1);
+    }
+
+    #[test]
+    fn test_new_untracked() {
+        let inner = CountingDestroyer::new();
+        let factory = AtomicDestructor::new_untracked(inner.clone());
+        assert_eq!(factory.counter(), 0);
+        assert!(factory.is_stealth());
+        assert!(!factory.is_destroyed());
+
+        let t_1 = factory.clone();
+        assert!(!t_1.is_stealth());
+        assert_eq!(factory.counter(), 1);
+
+        let t_2 = t_1.clone();
+        assert_eq!(factory.counter(), 2);
+
+        drop(t_1);
+        assert_eq!(factory.counter(), 1);
+        assert!(!factory.is_destroyed());
+
+        // Only the factory remains
+        drop(t_2);
+        assert_eq!(factory.counter(), 0);
+        assert!(factory.is_destroyed());
+        assert_eq!(inner.destroyed(), 1);
+
+        // Dropping the factory doesn't destroy again
+        drop(factory);
+        assert_eq!(inner.destroyed(), 1);
+    }
+
+    #[test]
+    fn test_swap() {
+        let inner_a = CountingDestroyer::new();
+        let inner_b = CountingDestroyer::new();
+
+        let mut a = AtomicDestructor::new(inner_a.clone());
+        let a_1 = a.clone();
+        assert_eq!(a.counter(), 2);
+
+        let mut b = AtomicDestructor::new_untracked(inner_b.clone());
+        let b_1 = b.clone();
+        drop(b_1);
+        assert!(b.is_destroyed());
+
+        AtomicDestructor::swap(&mut a, &mut b);
+
+        // Nothing was destroyed or counted by the swap
+        assert_eq!(inner_a.destroyed(), 0);
+        assert_eq!(inner_b.destroyed(), 1);
+
+        assert_eq!(a.counter(), 0);
+        assert!(a.is_destroyed());
+        assert!(a.is_stealth());
+        assert!(Arc::ptr_eq(
+            &a.inner().destroyed_counter(),
+            &inner_b.destroyed_counter()
+        ));
+
+        assert_eq!(b.counter(), 2);
+        assert!(!b.is_destroyed());
+        assert!(!b.is_stealth());
+        assert!(Arc::ptr_eq(
+            &b.inner().destroyed_counter(),
+            &inner_a.destroyed_counter()
+        ));
+
+        drop(a_1);
+        assert_eq!(b.counter(), 1);
+
+        drop(b);
+        assert_eq!(inner_a.destroyed(), 1);
+    }
+
+    #[test]
+    fn test_compare_and_destroy() {
+        let inner = CountingDestroyer::new();
+        let t = AtomicDestructor::new(inner.clone());
+        let t_1 = t.clone();
+
+        assert_eq!(t.compare_and_destroy(1), Err(2));
+        assert!(!t.is_destroyed());
+
+        drop(t_1);
+        assert_eq!(t.compare_and_destroy(1), Ok(()));
+        assert!(t.is_destroyed());
+        assert_eq!(t.counter(), 0);
+        assert_eq!(inner.destroyed(), 1);
+
+        drop(t);
+        assert_eq!(inner.destroyed(), 1);
+    }
+
+    #[test]
+    fn test_compare_and_destroy_racing_clone() {
+        for _ in 0..1000 {
+            let inner = CountingDestroyer::new();
+            let t = AtomicDestructor::new(inner.clone());
+            let t_1 = t.stealth_clone();
+
+            let handle = std::thread::spawn(move || t_1.clone());
+
+            match t.compare_and_destroy(1) {
+                Ok(()) => {
+                    assert!(t.is_destroyed());
+                    assert_eq!(inner.destroyed(), 1);
+                }
+                Err(actual) => {
+                    assert_eq!(actual, 2);
+                    assert!(!t.is_destroyed());
+                    assert_eq!(inner.destroyed(), 0);
+                }
+            }
+
+            drop(handle.join().unwrap());
+            drop(t);
+            assert_eq!(inner.destroyed(), 1);
+        }
+    }
+
+    #[test]
+    fn test_clone_limit() {
+        let t = AtomicDestructor::builder(InternalTestingStealth)
+            .max_clones(3)
+            .build();
+        assert_eq!(t.clone_limit(), Some(3));
+
+        let t_1 = t.try_clone().unwrap();
+        let t_2 = t_1.clone();
+        assert_eq!(t.counter(), 3);
+
+        // Limit reached
+        assert!(t.try_clone().is_none());
+        assert!(t.stealth_clone().try_clone().is_none());
+        assert_eq!(t.counter(), 3);
+
+        drop(t_2);
+        assert_eq!(t.counter(), 2);
+
+        // Below the limit again
+        let t_3 = t.try_clone();
+        assert!(t_3.is_some());
+        assert_eq!(t.counter(), 3);
+
+        drop(t_1);
+        drop(t_3);
+        assert_eq!(t.counter(), 1);
+    }
+
+    #[test]
+    fn test_no_clone_limit() {
+        let t = AtomicDestructor::new(InternalTestingStealth);
+        assert_eq!(t.clone_limit(), None);
+
+        let clones: Vec<_> = (0..100).map(|_| t.try_clone().unwrap()).collect();
+        assert_eq!(t.counter(), 101);
+
+        drop(clones);
+        assert_eq!(t.counter(), 1);
+    }
+
+    #[test]
+    #[should_panic(expected = "clone limit reached")]
+    fn test_clone_limit_panic() {
+        let t = AtomicDestructor::builder(InternalTestingStealth)
+            .max_clones(1)
+            .build();
+        let _t_1 = t.clone();
+    }
+
+    #[test]
+    #[should_panic(expected = "max clones must be at least 1")]
+    fn test_clone_limit_zero() {
+        let _ = AtomicDestructor::builder(InternalTestingStealth).max_clones(0);
+    }
+
+    #[derive(Debug, Clone, Default)]
+    struct InternalTestingInit {
+        initialized: Arc<AtomicBool>,
+    }
+
+    impl AtomicDestroyer for InternalTestingInit {
+        fn clone_allowed(&self) -> bool {
+            !self.initialized.load(Ordering::SeqCst)
+        }
+
+        fn on_destroy(&self) {}
+    }
+
+    #[test]
+    fn test_clone_allowed() {
+        let t = AtomicDestructor::new(InternalTestingInit::default());
+        let t_1 = t.clone();
+        assert!(t.try_clone().is_some());
+        assert_eq!(t.counter(), 2);
+
+        t.initialized.store(true, Ordering::SeqCst);
+        assert!(t.try_clone().is_none());
+        assert!(t.clone_if_alive().is_none());
+        assert_eq!(t.counter(), 2);
+
+        // Stealth clones are still allowed, but not their counted clones
+        let stealth = t.stealth_clone();
+        assert!(stealth.try_clone().is_none());
+
+        drop(t_1);
+        drop(t);
+        assert!(stealth.is_destroyed());
+    }
+
+    #[test]
+    #[should_panic(expected = "clone not allowed")]
+    fn test_clone_not_allowed_panic() {
+        let t = AtomicDestructor::new(InternalTestingInit::default());
+        t.initialized.store(true, Ordering::SeqCst);
+        let _t_1 = t.clone();
+    }
+
+    #[test]
+    #[should_panic(expected = "clone not allowed")]
+    fn test_counted_clone_not_allowed_panic() {
+        let t = AtomicDestructor::new(InternalTestingInit::default());
+        t.initialized.store(true, Ordering::SeqCst);
+        let _t_1 = t.counted_clone();
+    }
+
+    #[derive(Debug, Clone, Default)]
+    struct InternalTestingCloneChecks {
+        checks: Arc<AtomicUsize>,
+    }
+
+    impl AtomicDestroyer for InternalTestingCloneChecks {
+        fn clone_allowed(&self) -> bool {
+            self.checks.fetch_add(1, Ordering::SeqCst);
+            true
+        }
+
+        fn on_destroy(&self) {}
+    }
+
+    #[test]
+    fn test_clone_allowed_checked_once() {
+        let t = AtomicDestructor::new(InternalTestingCloneChecks::default());
+        let _t_1 = t.clone();
+        assert_eq!(t.checks.load(Ordering::SeqCst), 1);
+
+        let _t_2 = t.counted_clone();
+        assert_eq!(t.checks.load(Ordering::SeqCst), 2);
+    }
+
+    #[derive(Debug, Clone, Default)]
+    struct InternalTestingReentrant {
+        destroyed: Arc<AtomicUsize>,
+        captured: Arc<std::sync::Mutex<Option<AtomicDestructor<InternalTestingReentrant>>>>,
+    }
+
+    impl AtomicDestroyer for InternalTestingReentrant {
+        fn on_destroy(&self) {
+            self.destroyed.fetch_add(1, Ordering::SeqCst);
+
+            // Drop the captured instance (and a clone of it) from within the destruction
+            let captured = self.captured.lock().unwrap().take();
+            if let Some(captured) = captured {
+                let cloned = captured.clone();
+                drop(captured);
+                drop(cloned);
+            }
+        }
+    }
+
+    #[test]
+    fn test_reentrant_drop_of_stealth_clone() {
+        let inner = InternalTestingReentrant::default();
+        let t = AtomicDestructor::new(inner.clone());
+        *inner.captured.lock().unwrap() = Some(t.stealth_clone());
+
+        drop(t);
+        assert_eq!(inner.destroyed.load(Ordering::SeqCst), 1);
+        assert!(inner.captured.lock().unwrap().is_none());
+    }
+
+    #[test]
+    fn test_reentrant_drop_of_counted_clone() {
+        let inner = InternalTestingReentrant::default();
+        let t = AtomicDestructor::new(inner.clone());
+        *inner.captured.lock().unwrap() = Some(t.clone());
+        assert_eq!(t.counter(), 2);
+
+        assert_eq!(t.compare_and_destroy(2), Ok(()));
+        assert_eq!(inner.destroyed.load(Ordering::SeqCst), 1);
+        assert!(inner.captured.lock().unwrap().is_none());
+
+        drop(t);
+        assert_eq!(inner.destroyed.load(Ordering::SeqCst), 1);
+    }
+
+    #[derive(Debug, Clone)]
+    struct InternalTestingOrder {
+        id: u8,
+        log: Arc<std::sync::Mutex<Vec<u8>>>,
+    }
+
+    impl AtomicDestroyer for InternalTestingOrder {
+        fn on_destroy(&self) {
+            self.log.lock().unwrap().push(self.id);
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn test_destroys_after() {
+        let log = Arc::new(std::sync::Mutex::new(Vec::new()));
+
+        let a = AtomicDestructor::new(InternalTestingOrder {
+            id: 1,
+            log: log.clone(),
+        });
+        let b = AtomicDestructor::new(InternalTestingOrder {
+            id: 2,
+            log: log.clone(),
+        });
+
+        a.destroys_after(&b);
+        assert_eq!(b.counter(), 2);
+
+        // `b` is kept alive by `a`
+        drop(b);
+        assert!(log.lock().unwrap().is_empty());
+
+        drop(a);
+        assert_eq!(*log.lock().unwrap(), vec![1, 2]);
+    }
+
+    #[test]
+    fn test_clone_n() {
+        let t = AtomicDestructor::new(InternalTestingStealth);
+
+        let clones = t.clone_n(5);
+        assert_eq!(clones.len(), 5);
+        assert_eq!(t.counter(), 6);
+        assert!(clones.iter().all(|c| !c.is_stealth()));
+
+        assert!(t.clone_n(0).is_empty());
+        assert_eq!(t.counter(), 6);
+
+        // From a stealth instance
+        let clones_1 = t.stealth_clone().clone_n(2);
+        assert_eq!(t.counter(), 8);
+
+        drop(clones);
+        drop(clones_1);
+        assert_eq!(t.counter(), 1);
+        assert!(!t.is_destroyed());
+    }
+
+    #[test]
+    #[should_panic(expected = "clone limit reached")]
+    fn test_clone_n_limit() {
+        let t = AtomicDestructor::builder(InternalTestingStealth)
+            .max_clones(3)
+            .build();
+        let _clones = t.clone_n(3);
+    }
+
+    #[test]
+    fn test_drop_all() {
+        let log = Arc::new(std::sync::Mutex::new(Vec::new()));
+
+        let t = AtomicDestructor::new(InternalTestingOrder {
+            id: 0,
+            log: log.clone(),
+        });
+
+        let mut clones = t.clone_n(4);
+        for (i, c) in clones.iter_mut().enumerate() {
+            c.id = i as u8 + 1;
+        }
+        drop(t);
+
+        AtomicDestructor::drop_all(clones);
+
+        // Destroyed by the final element
+        assert_eq!(*log.lock().unwrap(), vec![4]);
+    }
+
+    #[test]
+    fn test_counter_saturated() {
+        let t = AtomicDestructor::new(InternalTestingStealth);
+        assert!(!t.counter_saturated());
+
+        t.state.counter.store(usize::MAX - 2, Ordering::SeqCst);
+
+        let t_1 = t.clone();
+        assert_eq!(t.counter(), usize::MAX - 1);
+        assert!(!t.counter_saturated());
+
+        let t_2 = t.clone();
+        assert_eq!(t.counter(), usize::MAX);
+        assert!(t.counter_saturated());
+
+        let t_3 = t.clone();
+        assert_eq!(t.counter(), usize::MAX);
+
+        drop(t_1);
+        drop(t_2);
+        drop(t_3);
+
+        // The flag is sticky
+        assert!(t.counter_saturated());
+    }
+
+    #[test]
+    fn test_counts() {
+        let t = AtomicDestructor::new(InternalTestingStealth);
+        assert_eq!(t.counts(), (1, 0));
+        assert_eq!(t.total_count(), 1);
+
+        let s_1 = t.stealth_clone();
+        let t_1 = s_1.clone();
+        let s_2 = t_1.stealth_clone();
+        assert_eq!(t.counts(), (2, 2));
+        assert_eq!(t.stealth_count(), 2);
+        assert_eq!(t.total_count(), 4);
+
+        drop(s_1);
+        assert_eq!(t.counts(), (2, 1));
+
+        drop(t_1);
+        assert_eq!(t.counts(), (1, 1));
+
+        drop(t);
+        assert_eq!(s_2.counts(), (0, 1));
+        assert_eq!(s_2.total_count(), 1);
+    }
+
+    #[test]
+    fn test_counts_untracked() {
+        let factory = AtomicDestructor::new_untracked(InternalTestingStealth);
+        assert_eq!(factory.counts(), (0, 1));
+
+        let t = factory.clone();
+        assert_eq!(factory.counts(), (1, 1));
+
+        drop(t);
+        assert_eq!(factory.counts(), (0, 1));
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn test_on_destroy_subscribe_order() {
+        let log = Arc::new(std::sync::Mutex::new(Vec::new()));
+
+        let t = AtomicDestructor::new(InternalTestingOrder {
+            id: 2,
+            log: log.clone(),
+        });
+
+        let l = log.clone();
+        t.on_destroy_subscribe_post(move || l.lock().unwrap().push(3));
+        let l = log.clone();
+        t.on_destroy_subscribe_pre(move || l.lock().unwrap().push(1));
+        let l = log.clone();
+        t.on_destroy_subscribe_post(move || l.lock().unwrap().push(4));
+
+        let stealth = t.stealth_clone();
+        drop(t);
+        assert_eq!(*log.lock().unwrap(), vec![1, 2, 3, 4]);
+
+        // Already destroyed: called immediately
+        let l = log.clone();
+        stealth.on_destroy_subscribe_post(move || l.lock().unwrap().push(5));
+        assert_eq!(*log.lock().unwrap(), vec![1, 2, 3, 4, 5]);
+    }
+
+    #[test]
+    fn test_make_mut_unique() {
+        let inner = CountingDestroyer::new();
+        let destroyed = inner.destroyed_counter();
+        let mut t = AtomicDestructor::new(inner.clone());
+        let stealth = t.stealth_clone();
+
+        let _inner: &mut CountingDestroyer = t.make_mut();
+
+        // Same lifecycle
+        assert_eq!(t.counts(), (1, 1));
+        assert_eq!(stealth.counts(), (1, 1));
+
+        drop(t);
+        assert!(stealth.is_destroyed());
+        assert_eq!(destroyed.load(Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn test_make_mut_shared() {
+        let inner = CountingDestroyer::new();
+        let destroyed = inner.destroyed_counter();
+        let mut t = AtomicDestructor::builder(inner.clone())
+            .max_clones(2)
+            .build();
+        let t_1 = t.clone();
+
+        let _inner: &mut CountingDestroyer = t.make_mut();
+
+        // Detached: the old lifecycle isn't destroyed
+        assert_eq!(t.counter(), 1);
+        assert_eq!(t_1.counter(), 1);
+        assert_eq!(t.clone_limit(), Some(2));
+        assert!(!t_1.is_destroyed());
+        assert_eq!(destroyed.load(Ordering::SeqCst), 0);
+
+        drop(t_1);
+        assert!(!t.is_destroyed());
+        assert_eq!(destroyed.load(Ordering::SeqCst), 1);
+
+        drop(t);
+        assert_eq!(destroyed.load(Ordering::SeqCst), 2);
+    }
+
+    #[test]
+    fn test_raw_state() {
+        let t = AtomicDestructor::new(InternalTestingStealth);
+        let stealth = t.stealth_clone();
+        assert_eq!(
+            t.__state(),
+            RawState {
+                counter: 1,
+                destroyed: false,
+                stealth: false,
+            }
+        );
+
+        drop(t);
+        assert_eq!(
+            stealth.__state(),
+            RawState {
+                counter: 0,
+                destroyed: true,
+                stealth: true,
+            }
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "thread-tracking")]
+    fn test_counts_by_thread() {
+        let t = AtomicDestructor::new(InternalTestingStealth);
+        let _stealth = t.stealth_clone();
+        let main_id = thread::current().id();
+
+        let mut clones = t.clone_n(2);
+
+        let remote = t.clone();
+        let (remote_id, remote_clones) = thread::spawn(move || {
+            let clones = remote.clone_n(3);
+            (thread::current().id(), (remote, clones))
+        })
+        .join()
+        .unwrap();
+
+        let counts = t.counts_by_thread();
+        assert_eq!(counts.len(), 2);
+        assert_eq!(counts[&main_id], 4);
+        assert_eq!(counts[&remote_id], 3);
+        assert_eq!(counts.values().sum::<usize>(), t.counter());
+
+        // Dropping on another thread decreases the creating thread entry
+        thread::spawn(move || drop(remote_clones)).join().unwrap();
+        clones.pop();
+
+        let counts = t.counts_by_thread();
+        assert_eq!(counts.len(), 1);
+        assert_eq!(counts[&main_id], 2);
+    }
+
+    #[test]
+    fn test_transfer_to_thread() {
+        let inner = CountingDestroyer::new();
+        let destroyed = inner.destroyed_counter();
+        let t = AtomicDestructor::new(inner.clone());
+
+        let (stealth, counted) = t.transfer_to_thread();
+        assert!(stealth.is_stealth());
+        assert!(!counted.is_stealth());
+        assert_eq!(stealth.counts(), (1, 1));
+
+        std::thread::spawn(move || drop(counted)).join().unwrap();
+
+        assert!(stealth.is_destroyed());
+        assert_eq!(destroyed.load(Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn test_from_parts() {
+        let inner = CountingDestroyer::new();
+        let destroyed = inner.destroyed_counter();
+        let t = AtomicDestructor::new(InternalTestingStealth);
+
+        // Second typed view over the same lifecycle
+        let view = AtomicDestructor::from_parts(inner.clone(), t.state(), false);
+        let observer = AtomicDestructor::from_parts(InternalTestingStealth, t.state(), true);
+        assert_eq!(t.counts(), (2, 1));
+        assert_eq!(view.counts(), (2, 1));
+
+        drop(t);
+        assert!(!view.is_destroyed());
+
+        drop(view);
+        assert!(observer.is_destroyed());
+        assert_eq!(destroyed.load(Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn test_debug() {
+        let t = AtomicDestructor::new(InternalTestingStealth);
+        assert_eq!(
+            format!("{:?}", t),
+            "AtomicDestructor { counter: 1, is_destroyed: false, is_stealth: false, is_unique: true, inner: InternalTestingStealth }"
+        );
+
+        let stealth = t.stealth_clone();
+        drop(t);
+        assert_eq!(
+            format!("{:?}", stealth),
+            "AtomicDestructor { counter: 0, is_destroyed: true, is_stealth: true, is_unique: false, inner: InternalTestingStealth }"
+        );
+    }
+
+    #[test]
+    fn test_destroy_and_into_inner() {
+        let inner = CountingDestroyer::new();
+        let destroyed = inner.destroyed_counter();
+        let t = AtomicDestructor::new(inner.clone());
+        let observer = t.stealth_clone();
+
+        // Not unique
+        let t_1 = t.clone();
+        let t = t.destroy_and_into_inner().unwrap_err();
+        assert!(!t.is_destroyed());
+        assert_eq!(t.counter(), 2);
+        drop(t_1);
+
+        // Stealth
+        let observer = observer.destroy_and_into_inner().unwrap_err();
+        assert!(!observer.is_destroyed());
+
+        // Unique
+        let inner = t.destroy_and_into_inner().unwrap();
+        assert!(Arc::ptr_eq(&inner.destroyed_counter(), &destroyed));
+        assert!(observer.is_destroyed());
+        assert_eq!(destroyed.load(Ordering::SeqCst), 1);
+
+        drop(inner);
+        drop(observer);
+        assert_eq!(destroyed.load(Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn test_try_destroy_now() {
+        let inner = CountingDestroyer::new();
+        let destroyed = inner.destroyed_counter();
+        let observer = {
+            let t = AtomicDestructor::new(inner.clone());
+            let observer = t.stealth_clone();
+
+            // Not unique
+            let t_1 = t.clone();
+            let t = t.try_destroy_now().unwrap_err();
+            assert!(!t.is_destroyed());
+            drop(t_1);
+
+            // Stealth
+            let observer = observer.try_destroy_now().unwrap_err();
+
+            // Destroyed before the end of the scope
+            assert!(t.try_destroy_now().is_ok());
+            assert!(observer.is_destroyed());
+            assert_eq!(observer.counter(), 0);
+            assert_eq!(destroyed.load(Ordering::SeqCst), 1);
+
+            // Already destroyed
+            observer.try_destroy_now().unwrap_err()
+        };
+
+        drop(observer);
+        assert_eq!(destroyed.load(Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn test_transfer_inner() {
+        let inner = CountingDestroyer::new();
+        let destroyed = inner.destroyed_counter();
+        let t = AtomicDestructor::new(inner.clone());
+        let observer = t.stealth_clone();
+
+        // Not unique
+        let t_1 = t.clone();
+        assert!(t_1.transfer_inner().is_none());
+        assert!(!observer.is_destroyed());
+
+        // Stealth
+        assert!(observer.stealth_clone().transfer_inner().is_none());
+
+        // Unique
+        let inner = t.transfer_inner().unwrap();
+        assert!(Arc::ptr_eq(&inner.destroyed_counter(), &destroyed));
+        assert!(observer.is_destroyed());
+        assert_eq!(observer.counter(), 0);
+
+        drop(observer);
+        assert_eq!(destroyed.load(Ordering::SeqCst), 0);
+    }
+
+    #[derive(Debug)]
+    struct InternalTestingClones {
+        clones: Arc<AtomicUsize>,
+    }
+
+    impl Clone for InternalTestingClones {
+        fn clone(&self) -> Self {
+            self.clones.fetch_add(1, Ordering::SeqCst);
+            Self {
+                clones: self.clones.clone(),
+            }
+        }
+    }
+
+    impl AtomicDestroyer for InternalTestingClones {
+        fn on_destroy(&self) {}
+    }
+
+    #[test]
+    fn test_version() {
+        let mut t = AtomicDestructor::new(NoopDestroyer(0));
+        let observer = t.stealth_clone();
+        assert_eq!(observer.version(), 0);
+
+        t.replace_inner(NoopDestroyer(1));
+        assert!(observer.version_changed(0));
+        assert_eq!(observer.version(), 1);
+
+        t.map_in_place(|inner| inner.0 += 1).unwrap();
+        assert_eq!(t.try_map_in_place(|_| Err(())), Err(TryMapError::Map(())));
+        assert_eq!(observer.version(), 3);
+
+        // Unique: mutated in place
+        t.make_mut().0 += 1;
+        assert_eq!(observer.version(), 4);
+        assert!(!observer.version_changed(4));
+
+        // Not unique: detached, with its own version
+        let mut t_1 = t.clone();
+        t_1.make_mut().0 += 1;
+        assert_eq!(t_1.version(), 1);
+        assert_eq!(observer.version(), 4);
+    }
+
+    #[test]
+    fn test_into_inner_moves() {
+        let clones = Arc::new(AtomicUsize::new(0));
+
+        let t = AtomicDestructor::new(InternalTestingClones {
+            clones: clones.clone(),
+        });
+        let inner = t.transfer_inner().unwrap();
+        assert_eq!(clones.load(Ordering::SeqCst), 0);
+
+        let t = AtomicDestructor::new(inner);
+        let inner = t.destroy_and_into_inner().unwrap();
+        assert_eq!(clones.load(Ordering::SeqCst), 0);
+        assert!(Arc::ptr_eq(&inner.clones, &clones));
+    }
+
+    #[test]
+    fn test_transfer_inner_racing_clone() {
+        let t = AtomicDestructor::new(CountingDestroyer::new());
+        let t_1 = t.clone();
+
+        let cloner = std::thread::spawn(move || {
+            for _ in 0..10_000 {
+                drop(t_1.clone());
+            }
+            t_1
+        });
+
+        // Never unique: the failed transfers must not affect the concurrent clones
+        for _ in 0..10_000 {
+            assert!(t.clone().transfer_inner().is_none());
+        }
+
+        let t_1 = cloner.join().unwrap();
+        assert_eq!(t.counter(), 2);
+        assert!(!t.is_destroyed());
+
+        drop(t_1);
+        drop(t);
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn test_transfer_inner_notify() {
+        let t = AtomicDestructor::new(CountingDestroyer::new());
+        let observer = t.stealth_clone();
+
+        let notified = Arc::new(AtomicUsize::new(0));
+        let n = notified.clone();
+        observer.on_destroy_subscribe_post(move || {
+            n.fetch_add(1, Ordering::SeqCst);
+        });
+
+        // Not unique: the lifecycle is still destroyable
+        let t_1 = t.clone();
+        assert!(t_1.transfer_inner().is_none());
+        assert_eq!(t.counter(), 1);
+
+        assert!(t.transfer_inner().is_some());
+        assert_eq!(notified.load(Ordering::SeqCst), 1);
+
+        // Subscribed after the transfer: called immediately
+        let n = notified.clone();
+        observer.on_destroy_subscribe_post(move || {
+            n.fetch_add(1, Ordering::SeqCst);
+        });
+        assert_eq!(notified.load(Ordering::SeqCst), 2);
+    }
+
+    #[test]
+    fn test_to_owned_inner() {
+        let inner = CountingDestroyer::new();
+        let destroyed = inner.destroyed_counter();
+        let t = AtomicDestructor::new(inner.clone());
+        let observer = t.stealth_clone();
+
+        let inner: CountingDestroyer = t.to_owned_inner();
+        assert!(Arc::ptr_eq(&inner.destroyed_counter(), &destroyed));
+        assert_eq!(t.counter(), 1);
+        assert!(observer.try_to_owned_inner().is_some());
+
+        drop(t);
+        assert!(observer.try_to_owned_inner().is_none());
+    }
+
+    #[test]
+    #[should_panic(expected = "inner already destroyed")]
+    fn test_to_owned_inner_destroyed() {
+        let t = AtomicDestructor::new(InternalTestingStealth);
+        let observer = t.stealth_clone();
+        drop(t);
+        let _ = observer.to_owned_inner();
+    }
+
+    #[test]
+    fn test_pin() {
+        let mut pinned = Box::pin(AtomicDestructor::new(InternalTestingPinned {
+            value: 1,
+            _pinned: core::marker::PhantomPinned,
+        }));
+        let t_1 = pinned.clone();
+        let stealth = pinned.stealth_clone();
+        assert_eq!(pinned.counter(), 2);
+
+        pinned.as_mut().get_pin_mut().value = 2;
+        let old = pinned.as_mut().replace_inner(InternalTestingPinned {
+            value: 3,
+            _pinned: core::marker::PhantomPinned,
+        });
+        assert_eq!(old.value, 2);
+        assert_eq!(pinned.value, 3);
+        assert_eq!(t_1.value, 1);
+
+        // Moving out of the pin doesn't affect the lifecycle
+        let t = *Pin::into_inner(pinned);
+        assert_eq!(t.counter(), 2);
+        drop(t);
+        drop(t_1);
+        assert!(stealth.is_destroyed());
+    }
+
+    #[test]
+    fn test_into_static_and_send() {
+        fn spawn<F>(f: F) -> std::thread::JoinHandle<()>
+        where
+            F: FnOnce() + Send + 'static,
+        {
+            std::thread::spawn(f)
+        }
+
+        let t = AtomicDestructor::new(InternalTestingStealth)
+            .into_static()
+            .into_send();
+        let t_1 = t.clone();
+        spawn(move || drop(t_1)).join().unwrap();
+        assert_eq!(t.counter(), 1);
+    }
+
+    #[test]
+    fn test_effective_counter() {
+        let t = AtomicDestructor::new(InternalTestingStealth);
+        let t_1 = t.clone();
+        let stealth = t.stealth_clone();
+        assert_eq!(t.effective_counter(), 2);
+        assert_eq!(stealth.effective_counter(), 2);
+
+        drop(t_1);
+        assert_eq!(t.effective_counter(), 1);
+
+        drop(t);
+        assert_eq!(stealth.effective_counter(), 0);
+
+        // Raw counter not zero while destroyed
+        let t = AtomicDestructor::builder(InternalTestingStealth)
+            .destroy_trigger(DestroyTrigger::FirstDrop)
+            .build();
+        let t_1 = t.clone();
+        drop(t);
+        assert_eq!(t_1.counter(), 1);
+        assert_eq!(t_1.effective_counter(), 0);
+    }
+
+    #[test]
+    fn test_pointer() {
+        let t = AtomicDestructor::new(InternalTestingStealth);
+        let t_1 = t.clone();
+        let stealth = t.stealth_clone();
+        assert_eq!(format!("{:p}", t), format!("{:p}", t_1));
+        assert_eq!(format!("{:p}", t), format!("{:p}", stealth));
+
+        let other = AtomicDestructor::new(InternalTestingStealth);
+        assert_ne!(format!("{:p}", t), format!("{:p}", other));
+    }
+
+    #[test]
+    fn test_panic_location() {
+        use std::panic::{self, AssertUnwindSafe};
+        use std::sync::Mutex;
+
+        let locations: Arc<Mutex<Vec<(String, u32)>>> = Arc::new(Mutex::new(Vec::new()));
+
+        let t = AtomicDestructor::builder(InternalTestingStealth)
+            .max_clones(1)
+            .build();
+
+        let prev_hook = panic::take_hook();
+        let hook_locations = locations.clone();
+        panic::set_hook(Box::new(move |info| {
+            if let Some(location) = info.location() {
+                hook_locations
+                    .lock()
+                    .unwrap()
+                    .push((location.file().to_string(), location.line()));
+            }
+        }));
+        let line = line!() + 1;
+        let res = panic::catch_unwind(AssertUnwindSafe(|| t.clone()));
+        let res_n = panic::catch_unwind(AssertUnwindSafe(|| t.clone_n(1)));
+        panic::set_hook(prev_hook);
+
+        assert!(res.is_err());
+        assert!(res_n.is_err());
+
+        // Other tests may panic concurrently
+        let locations = locations.lock().unwrap();
+        assert!(locations.contains(&(file!().to_string(), line)));
+        assert!(locations.contains(&(file!().to_string(), line + 1)));
+    }
+
+    #[test]
+    fn test_peek() {
+        let t = AtomicDestructor::new(InternalTestingStealth);
+        let stealth = t.stealth_clone();
+        assert!(t.peek().is_some());
+        assert!(stealth.peek().is_some());
+
+        drop(t);
+        assert!(stealth.peek().is_none());
+    }
+
+    #[test]
+    fn test_load_consistent() {
+        for _ in 0..20 {
+            let t = AtomicDestructor::new(InternalTestingStealth);
+            let observer = t.stealth_clone();
+
+            let mut clones = t.clone_n(7);
+            clones.push(t);
+
+            let handles: Vec<_> = clones
+                .into_iter()
+                .map(|clone| std::thread::spawn(move || drop(clone)))
+                .collect();
+
+            let mut last: usize = usize::MAX;
+            loop {
+                let (counter, destroyed) = observer.load_consistent();
+                assert!(counter <= last);
+                last = counter;
+
+                if destroyed {
+                    assert_eq!(counter, 0);
+                    break;
+                }
+            }
+
+            for handle in handles.into_iter() {
+                handle.join().unwrap();
+            }
+        }
+    }
+
+    #[test]
+    fn test_clone_and_name() {
+        let t = AtomicDestructor::new(InternalTestingStealth);
+        assert_eq!(t.clone_name(), None);
+
+        let named = t.clone_and_name("worker");
+        assert_eq!(named.clone_name(), Some("worker"));
+        assert_eq!(t.counter(), 2);
+
+        // Clones preserve the name
+        let named_1 = named.clone();
+        assert_eq!(named_1.clone_name(), Some("worker"));
+        assert_eq!(named.stealth_clone().clone_name(), Some("worker"));
+        assert_eq!(t.counter(), 3);
+
+        #[cfg(feature = "tracing")]
+        assert_eq!(named_1.log_name(), Some(String::from("worker")));
+    }
+
+    #[test]
+    fn test_map_in_place() {
+        let mut t = AtomicDestructor::new(NoopDestroyer(1));
+        let mut stealth = t.stealth_clone();
+
+        assert_eq!(t.map_in_place(|v| v.0 += 1), Ok(()));
+        assert_eq!(t.0, 2);
+
+        assert_eq!(
+            t.try_map_in_place(|v| {
+                v.0 += 1;
+                Err::<(), _>("fail")
+            }),
+            Err(TryMapError::Map("fail"))
+        );
+        assert_eq!(t.0, 3);
+
+        drop(t);
+        assert_eq!(stealth.map_in_place(|v| v.0 += 1), Err(DestroyedError));
+        assert_eq!(
+            stealth.try_map_in_place(|_| Ok::<(), ()>(())),
+            Err(TryMapError::Destroyed(DestroyedError))
+        );
+        assert_eq!(stealth.0, 1);
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn test_on_stealth_drop() {
+        let dropped = Arc::new(AtomicUsize::new(0));
+
+        let t = AtomicDestructor::new(InternalTestingStealth);
+        let d = dropped.clone();
+        t.on_stealth_drop(move || {
+            d.fetch_add(1, Ordering::SeqCst);
+        });
+
+        let stealth = t.stealth_clone();
+        let stealth_1 = stealth.stealth_clone();
+        let t_1 = t.clone();
+
+        drop(t_1);
+        drop(stealth);
+        assert_eq!(dropped.load(Ordering::SeqCst), 1);
+
+        drop(t);
+        drop(stealth_1);
+        assert_eq!(dropped.load(Ordering::SeqCst), 2);
+    }
+
+    #[test]
+    fn test_counted_clone() {
+        let t = AtomicDestructor::new(NoopDestroyer(()));
+        let (t_1, counter) = t.counted_clone();
+        assert_eq!(counter, 2);
+
+        let handles: Vec<_> = t
+            .clone_n(4)
+            .into_iter()
+            .map(|t| {
+                std::thread::spawn(move || {
+                    let mut clones = Vec::new();
+                    let mut counters = Vec::new();
+                    for _ in 0..100 {
+                        let (clone, counter) = t.counted_clone();
+                        clones.push(clone);
+                        counters.push(counter);
+                    }
+                    (t, clones, counters)
+                })
+            })
+            .collect();
+
+        let mut all_counters = Vec::new();
+        let mut holders = Vec::new();
+        for handle in handles.into_iter() {
+            let (t, clones, counters) = handle.join().unwrap();
+            // Each thread observes increasing values
+            assert!(counters.windows(2).all(|w| w[0] < w[1]));
+            all_counters.extend(counters);
+            holders.push((t, clones));
+        }
+
+        // Every increment produced a distinct value
+        all_counters.sort_unstable();
+        all_counters.dedup();
+        assert_eq!(all_counters.len(), 400);
+        assert_eq!(t.counter(), 2 + 4 + 400);
+        assert_eq!(*all_counters.last().unwrap(), t.counter());
+
+        drop(holders);
+        drop(t_1);
+        assert_eq!(t.counter(), 1);
+    }
+
+    #[test]
+    fn test_try_destroy() {
+        let inner = CountingDestroyer::new();
+        let destroyed = inner.destroyed_counter();
+
+        for _ in 0..50 {
+            let t = AtomicDestructor::new(inner.clone());
+            let barrier = Arc::new(std::sync::Barrier::new(2));
+
+            let handles: Vec<_> = t
+                .clone_n(2)
+                .into_iter()
+                .map(|t| {
+                    let barrier = barrier.clone();
+                    std::thread::spawn(move || {
+                        barrier.wait();
+                        t.try_destroy()
+                    })
+                })
+                .collect();
+
+            let results: Vec<bool> = handles.into_iter().map(|h| h.join().unwrap()).collect();
+            assert_eq!(results.iter().filter(|r| **r).count(), 1);
+            assert!(t.is_destroyed());
+            assert!(!t.try_destroy());
+        }
+
+        assert_eq!(destroyed.load(Ordering::SeqCst), 50);
+    }
+
+    #[test]
+    fn test_single_destruction_under_contention() {
+        let inner = CountingDestroyer::new();
+        let destroyed = inner.destroyed_counter();
+
+        for _ in 0..100 {
+            let t = AtomicDestructor::new(inner.clone());
+            let mut clones = t.clone_n(7);
+            clones.push(t);
+
+            // Simulate a logic error: every drop observes a zero counter
+            clones[0].state.counter.store(0, Ordering::SeqCst);
+
+            let barrier = Arc::new(std::sync::Barrier::new(clones.len()));
+            let handles: Vec<_> = clones
+                .into_iter()
+                .map(|t| {
+                    let barrier = barrier.clone();
+                    std::thread::spawn(move || {
+                        barrier.wait();
+                        drop(t);
+                    })
+                })
+                .collect();
+
+            for handle in handles.into_iter() {
+                handle.join().unwrap();
+            }
+        }
+
+        assert_eq!(destroyed.load(Ordering::SeqCst), 100);
+    }
 
-        let t_2 = t_1.clone(); // Cloning stealth destructor
-        assert_eq!(t.inner.counter(), 2);
-        assert_eq!(t_1.inner.counter(), 2);
-        assert_eq!(t_2.inner.counter(), 2);
+    #[test]
+    fn test_destroy_trigger_last_drop() {
+        let inner = CountingDestroyer::new();
+        let destroyed = inner.destroyed_counter();
+        let t = AtomicDestructor::builder(inner.clone())
+            .destroy_trigger(DestroyTrigger::LastDrop)
+            .build();
+        assert_eq!(t.destroy_trigger(), DestroyTrigger::LastDrop);
 
-        let t_3 = t.clone(); // Cloning NON-stealth destructor
-        assert_eq!(t.inner.counter(), 3);
-        assert_eq!(t_1.inner.counter(), 3);
-        assert_eq!(t_2.inner.counter(), 3);
+        let mut clones = t.clone_n(2);
+        let observer = t.stealth_clone();
+        drop(t);
+        assert_eq!(destroyed.load(Ordering::SeqCst), 0);
 
-        drop(t_1); // Stealth
-        assert_eq!(t.inner.counter(), 3);
+        clones.pop();
+        assert_eq!(destroyed.load(Ordering::SeqCst), 0);
 
-        drop(t_2); // Classical
-        assert_eq!(t.inner.counter(), 2);
+        clones.pop();
+        assert!(observer.is_destroyed());
+        assert_eq!(destroyed.load(Ordering::SeqCst), 1);
+    }
 
-        drop(t_3); // Classical
-        assert_eq!(t.inner.counter(), 1);
+    #[test]
+    fn test_destroy_trigger_first_drop() {
+        let inner = CountingDestroyer::new();
+        let destroyed = inner.destroyed_counter();
+        let t = AtomicDestructor::builder(inner.clone())
+            .destroy_trigger(DestroyTrigger::FirstDrop)
+            .build();
+        assert_eq!(t.destroy_trigger(), DestroyTrigger::FirstDrop);
+
+        let mut clones = t.clone_n(2);
+
+        // Stealth drops don't trigger
+        drop(t.stealth_clone());
+        assert!(!t.is_destroyed());
+
+        drop(t);
+        assert!(clones[0].is_destroyed());
+        assert_eq!(destroyed.load(Ordering::SeqCst), 1);
+
+        // The remaining instances are inert
+        clones.pop();
+        clones.pop();
+        assert_eq!(destroyed.load(Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn test_group_id() {
+        let a = AtomicDestructor::new(InternalTestingStealth);
+        let a_1 = a.clone();
+        let a_2 = a.stealth_clone();
+        assert_eq!(a.group_id(), a_1.group_id());
+        assert_eq!(a.group_id(), a_2.group_id());
+
+        let b = AtomicDestructor::new(InternalTestingStealth);
+        assert!(b.group_id() > a.group_id());
+
+        // Detached into a new lifecycle
+        let mut a_3 = a.clone();
+        a_3.make_mut();
+        assert_ne!(a_3.group_id(), a.group_id());
+    }
+
+    #[derive(Debug, Clone, Default)]
+    struct InternalTestingColliding {
+        value: usize,
+    }
+
+    impl InternalTestingColliding {
+        fn counter(&self) -> usize {
+            self.value
+        }
+    }
+
+    impl AtomicDestroyer for InternalTestingColliding {
+        fn on_destroy(&self) {}
+    }
+
+    #[test]
+    fn test_inner() {
+        let mut t = AtomicDestructor::new(InternalTestingColliding { value: 42 });
+        let _t_1 = t.clone();
+
+        // The wrapper method wins over the inner one
+        assert_eq!(t.counter(), 2);
+        assert_eq!(t.inner().counter(), 42);
+
+        t.inner_mut().value = 7;
+        assert_eq!(t.inner().counter(), 7);
+        assert_eq!(t.value, 7);
+    }
+
+    #[derive(Debug, Clone, Default)]
+    struct InternalTestingCloneOnDestroy {
+        destroyed: Arc<AtomicUsize>,
+        captured: Arc<std::sync::Mutex<Option<AtomicDestructor<InternalTestingCloneOnDestroy>>>>,
+        cloned: Arc<std::sync::Mutex<Vec<AtomicDestructor<InternalTestingCloneOnDestroy>>>>,
+    }
+
+    impl AtomicDestroyer for InternalTestingCloneOnDestroy {
+        fn on_destroy(&self) {
+            self.destroyed.fetch_add(1, Ordering::SeqCst);
+
+            // Clone from within the destruction and keep the clone alive
+            if let Some(captured) = self.captured.lock().unwrap().as_ref() {
+                let clone = captured.clone();
+                assert!(clone.is_destroyed());
+                assert_eq!(clone.counter(), 0);
+                self.cloned.lock().unwrap().push(clone);
+            }
+        }
+    }
+
+    #[test]
+    fn test_clone_on_destroy() {
+        let inner = InternalTestingCloneOnDestroy::default();
+        let t = AtomicDestructor::new(inner.clone());
+        *inner.captured.lock().unwrap() = Some(t.stealth_clone());
+
+        drop(t);
+        assert_eq!(inner.destroyed.load(Ordering::SeqCst), 1);
+
+        let observer = inner.captured.lock().unwrap().take().unwrap();
+        assert_eq!(observer.counts(), (0, 1));
+
+        // Cloning after the destruction doesn't count either
+        let clone = observer.clone();
+        assert!(!clone.is_stealth());
+        assert_eq!(observer.counter(), 0);
+
+        drop(clone);
+        inner.cloned.lock().unwrap().clear();
+        assert_eq!(observer.counter(), 0);
+        assert_eq!(inner.destroyed.load(Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn test_poison() {
+        let inner = CountingDestroyer::new();
+        let destroyed = inner.destroyed_counter();
+        let t = AtomicDestructor::new(inner.clone());
+        let t_1 = t.clone();
+        let stealth = t.stealth_clone();
+        let observer = t.as_observer_weak();
+        assert!(t.clone_if_alive().is_some());
+        assert!(!t.is_poisoned());
+
+        t.poison();
+        assert!(stealth.is_poisoned());
+        assert!(!t.is_destroyed());
+        assert!(t.peek().is_none());
+        assert!(observer.peek().is_none());
+        assert!(t.clone_if_alive().is_none());
+
+        // Stealth drops don't destroy
+        drop(stealth);
+        assert_eq!(destroyed.load(Ordering::SeqCst), 0);
+
+        // The next drop destroys, regardless of the counter
+        drop(t_1);
+        assert!(t.is_destroyed());
+        assert_eq!(destroyed.load(Ordering::SeqCst), 1);
+
+        drop(t);
+        assert_eq!(destroyed.load(Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn test_is_destroyed_of() {
+        let t = AtomicDestructor::new(InternalTestingStealth);
+        let t_1 = t.clone();
+        let state: Arc<State> = t.state();
+        let shared: Vec<Arc<State>> = vec![state.clone(), state.clone()];
+        assert!(!is_destroyed_of(&state));
+
+        drop(t);
+        assert!(shared.iter().all(|state| !is_destroyed_of(state)));
+
+        drop(t_1);
+        assert!(shared.iter().all(is_destroyed_of));
+    }
+
+    #[test]
+    fn test_shares_state() {
+        let t = AtomicDestructor::new(InternalTestingStealth);
+        let view = AtomicDestructor::from_parts(CountingDestroyer::new(), t.state(), false);
+        assert!(shares_state(&t, &view));
+        assert!(shares_state(&view, &t.stealth_clone()));
+
+        let other = AtomicDestructor::new(CountingDestroyer::new());
+        assert!(!shares_state(&t, &other));
+        assert!(!shares_state(&view, &other));
+    }
+
+    #[test]
+    #[cfg(feature = "testing")]
+    fn test_simulate_concurrent_drops() {
+        let inner = CountingDestroyer::new();
+        let destroyed = inner.destroyed_counter();
+        let t = AtomicDestructor::new(inner.clone());
+        let clones = t.clone_n(4);
+
+        t.simulate_concurrent_drops(2);
+        assert_eq!(t.counter(), 3);
+        assert_eq!(destroyed.load(Ordering::SeqCst), 0);
+
+        t.simulate_concurrent_drops(t.counter());
+        assert!(t.is_destroyed());
+        assert_eq!(destroyed.load(Ordering::SeqCst), 1);
+
+        // Nothing happens anymore
+        t.simulate_concurrent_drops(t.counter());
+        drop(clones);
+        drop(t);
+        assert_eq!(destroyed.load(Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn test_add_sibling() {
+        let log = Arc::new(std::sync::Mutex::new(Vec::new()));
+
+        let mut a = AtomicDestructor::new(InternalTestingOrder {
+            id: 1,
+            log: log.clone(),
+        });
+        let a_1 = a.clone();
+        let mut b = AtomicDestructor::new(InternalTestingOrder {
+            id: 2,
+            log: log.clone(),
+        });
+
+        a.add_sibling(&mut b);
+        assert!(shares_state(&a, &b));
+        assert_eq!(a.counter(), 3);
+
+        // Already siblings
+        a.add_sibling(&mut b);
+        assert_eq!(a.counter(), 3);
+
+        drop(a);
+        drop(a_1);
+        assert!(log.lock().unwrap().is_empty());
+
+        // The last dropped instance is the sibling
+        drop(b);
+        assert_eq!(*log.lock().unwrap(), vec![1, 2]);
+    }
+
+    #[derive(Debug, Clone)]
+    #[cfg(feature = "std")]
+    struct InternalTestingSlow;
+
+    #[cfg(feature = "std")]
+    impl AtomicDestroyer for InternalTestingSlow {
+        fn on_destroy(&self) {
+            std::thread::sleep(std::time::Duration::from_millis(50));
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn test_last_destroy_duration() {
+        let t = AtomicDestructor::new(InternalTestingSlow);
+        let stealth = t.stealth_clone();
+        assert_eq!(t.last_destroy_duration(), None);
+
+        drop(t);
+        let duration = stealth.last_destroy_duration().unwrap();
+        assert!(duration >= std::time::Duration::from_millis(50));
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn test_shrink_to_last_blocking() {
+        let t = AtomicDestructor::new(InternalTestingStealth);
+        t.shrink_to_last_blocking();
+
+        let handles: Vec<_> = (0..4)
+            .map(|i| {
+                let holder = t.clone();
+                std::thread::spawn(move || {
+                    std::thread::sleep(std::time::Duration::from_millis(10 * i));
+                    drop(holder);
+                })
+            })
+            .collect();
+
+        t.shrink_to_last_blocking();
+        assert!(t.is_unique());
+        assert!(!t.is_destroyed());
+
+        for handle in handles.into_iter() {
+            handle.join().unwrap();
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn test_drop_without_waiters() {
+        let t = AtomicDestructor::new(InternalTestingStealth);
+        let stealth = t.stealth_clone();
+        drop(t.clone());
+        drop(stealth);
+
+        // Nothing to notify: the drops skip the locks
+        assert!(!t.state.has_drop_waiters.load(Ordering::SeqCst));
+        assert!(!t.state.has_stealth_drop_listeners.load(Ordering::SeqCst));
+
+        // Announced by the waiter: the drops notify it
+        let t_1 = t.clone();
+        let waiter = {
+            let t = t.clone();
+            std::thread::spawn(move || t.shrink_to_last_blocking())
+        };
+        while !t.state.has_drop_waiters.load(Ordering::SeqCst) {
+            std::thread::yield_now();
+        }
+        drop(t_1);
+        drop(t);
+        waiter.join().unwrap();
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    #[should_panic(expected = "stealth instance can't be the last one")]
+    fn test_shrink_to_last_blocking_stealth() {
+        let t = AtomicDestructor::new(InternalTestingStealth);
+        t.stealth_clone().shrink_to_last_blocking();
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn test_listeners_capacity() {
+        let t = AtomicDestructor::builder(InternalTestingStealth)
+            .listeners_capacity(16)
+            .build();
+        let capacity: usize = t.state.listeners_capacity();
+        assert!(capacity >= 32);
+
+        // No reallocation within the capacity
+        for _ in 0..16 {
+            t.on_destroy_subscribe_pre(|| {});
+            t.on_destroy_subscribe_post(|| {});
+        }
+        assert_eq!(t.state.listeners_capacity(), capacity);
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn test_shrink_listeners() {
+        let t = AtomicDestructor::new(InternalTestingStealth);
+        for _ in 0..5 {
+            t.on_destroy_subscribe_pre(|| {});
+            t.on_destroy_subscribe_post(|| {});
+            t.on_stealth_drop(|| {});
+        }
+
+        let capacity: usize = t.state.listeners_capacity();
+        assert!(capacity > 15);
+
+        t.shrink_listeners();
+        assert!(t.state.listeners_capacity() < capacity);
+        assert!(t.state.listeners_capacity() >= 15);
+    }
+
+    #[test]
+    #[cfg(feature = "timestamps")]
+    fn test_timestamps() {
+        let mut t = AtomicDestructor::new(InternalTestingStealth);
+        let t_1 = t.clone();
+        let stealth = t.stealth_clone();
+        let created_at = t.created_at();
+        assert_eq!(t_1.created_at(), created_at);
+        assert_eq!(stealth.created_at(), created_at);
+        assert_eq!(t.destroyed_at(), None);
+
+        drop(t_1);
+        assert_eq!(t.destroyed_at(), None);
+
+        assert!(t.try_destroy());
+        let destroyed_at = stealth.destroyed_at().unwrap();
+        assert!(destroyed_at >= created_at);
+        assert_eq!(t.destroyed_at(), Some(destroyed_at));
+
+        // First creation kept
+        assert_eq!(t.reset(), Ok(()));
+        assert_eq!(t.created_at(), created_at);
+        assert_eq!(t.destroyed_at(), None);
+    }
+
+    #[test]
+    fn test_reset_stale_drop() {
+        let inner = CountingDestroyer::new();
+        let destroyed = inner.destroyed_counter();
+        let mut t = AtomicDestructor::new(inner.clone());
+        let stale = t.clone();
+
+        // Not destroyed yet
+        assert_eq!(t.reset(), Err(ResetError::NotDestroyed));
+
+        assert!(t.try_destroy());
+        assert_eq!(destroyed.load(Ordering::SeqCst), 1);
+
+        assert_eq!(t.reset(), Ok(()));
+        assert_eq!(t.generation(), 1);
+        assert_eq!(t.counter(), 1);
+        assert!(!t.is_destroyed());
+        assert!(stale.is_destroyed());
+
+        let t_1 = t.clone();
+        assert_eq!(t_1.generation(), 1);
+        assert_eq!(t.counter(), 2);
+
+        // Clones of stale instances are stale too
+        let stale_1 = stale.clone();
+        assert_eq!(t.counter(), 2);
+
+        // Can't destroy the current generation
+        assert_eq!(stale.compare_and_destroy(2), Err(0));
+        assert_eq!(t.counter(), 2);
+        assert!(!t.is_destroyed());
+
+        // Late drops of the previous generation
+        drop(stale);
+        drop(stale_1);
+        assert_eq!(t.counter(), 2);
+        assert!(!t.is_destroyed());
+        assert_eq!(destroyed.load(Ordering::SeqCst), 1);
+
+        drop(t_1);
+        drop(t);
+        assert_eq!(destroyed.load(Ordering::SeqCst), 2);
+    }
+
+    #[derive(Debug, Clone)]
+    #[cfg(feature = "std")]
+    struct InternalTestingFallible {
+        fail: bool,
+    }
+
+    #[cfg(feature = "std")]
+    impl AtomicDestroyer for InternalTestingFallible {
+        fn on_destroy(&self) {}
+
+        fn on_destroy_fallible(
+            &self,
+            _stats: DestroyStats,
+        ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+            if self.fail {
+                return Err("flush failed".into());
+            }
+            Ok(())
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn test_last_destroy_error() {
+        let t = AtomicDestructor::new(InternalTestingFallible { fail: true });
+        let stealth = t.stealth_clone();
+        assert!(t.last_destroy_error().is_none());
+
+        drop(t);
+        let error = stealth.last_destroy_error().unwrap();
+        assert_eq!(error.inner().to_string(), "flush failed");
+        assert_eq!(error.to_string(), "destruction failed: flush failed");
+
+        let t = AtomicDestructor::new(InternalTestingFallible { fail: false });
+        let stealth = t.stealth_clone();
+        drop(t);
+        assert!(stealth.is_destroyed());
+        assert!(stealth.last_destroy_error().is_none());
+
+        // Default implementation
+        let t = AtomicDestructor::new(InternalTestingStealth);
+        let stealth = t.stealth_clone();
+        drop(t);
+        assert!(stealth.last_destroy_error().is_none());
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn test_heartbeat() {
+        let log = Arc::new(std::sync::Mutex::new(Vec::new()));
+
+        let a = AtomicDestructor::new(InternalTestingOrder {
+            id: 1,
+            log: log.clone(),
+        });
+        let b = AtomicDestructor::new(InternalTestingStealth);
+
+        let heartbeats: Vec<AtomicDestructor<Heartbeat>> =
+            vec![a.heartbeat(false), b.heartbeat(true)];
+        assert!(shares_state(&a, &heartbeats[0]));
+        assert!(shares_state(&b, &heartbeats[1]));
+        assert_eq!(a.counts(), (2, 0));
+        assert_eq!(b.counts(), (1, 1));
+
+        drop(b);
+        assert!(heartbeats[1].is_destroyed());
+
+        // The counted heartbeat keeps `a` alive
+        drop(a);
+        assert!(!heartbeats[0].is_destroyed());
+        assert!(log.lock().unwrap().is_empty());
+
+        drop(heartbeats);
+        assert_eq!(*log.lock().unwrap(), vec![1]);
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn test_heartbeat_destroy_error() {
+        // Destroyed by the heartbeat: the owner goes through the fallible hook
+        let a = AtomicDestructor::new(InternalTestingFallible { fail: true });
+        let heartbeat = a.heartbeat(false);
+        drop(a);
+        let stealth = heartbeat.stealth_clone();
+        drop(heartbeat);
+        assert!(stealth.is_destroyed());
+        assert!(stealth.last_destroy_error().is_some());
+        assert!(stealth.last_destroy_duration().is_some());
+
+        // Destroyed by the owner: the live inner is used, not the copy taken by the heartbeat
+        let mut b = AtomicDestructor::new(InternalTestingFallible { fail: false });
+        let heartbeat = b.heartbeat(false);
+        b.fail = true;
+        let stealth = b.stealth_clone();
+        drop(heartbeat);
+        drop(b);
+        assert!(stealth.is_destroyed());
+        assert!(stealth.last_destroy_error().is_some());
+    }
+
+    #[derive(Debug, Clone)]
+    #[cfg(feature = "std")]
+    struct InternalTestingPanic {
+        abort: bool,
+    }
+
+    #[cfg(feature = "std")]
+    impl AtomicDestroyer for InternalTestingPanic {
+        fn abort_on_destroy_panic(&self) -> bool {
+            self.abort
+        }
+
+        fn on_destroy(&self) {
+            panic!("destroy failed");
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    #[should_panic(expected = "destroy failed")]
+    fn test_destroy_panic_unwinds() {
+        let t = AtomicDestructor::new(InternalTestingPanic { abort: false });
+        drop(t);
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn test_destroy_panic_releases_waiters() {
+        use std::panic::{self, AssertUnwindSafe};
+
+        let mut t = AtomicDestructor::new(InternalTestingPanic { abort: false });
+        let notified = Arc::new(AtomicUsize::new(0));
+        let n = notified.clone();
+        t.on_destroy_subscribe_post(move || {
+            n.fetch_add(1, Ordering::SeqCst);
+        });
+
+        let waiter = {
+            let t = t.clone();
+            std::thread::spawn(move || {
+                t.shrink_to_last_blocking();
+                t
+            })
+        };
+        while !t.state.has_drop_waiters.load(Ordering::SeqCst) {
+            std::thread::yield_now();
+        }
+
+        assert!(panic::catch_unwind(AssertUnwindSafe(|| t.try_destroy())).is_err());
+        assert!(t.is_poisoned());
+
+        // Not stuck on the poisoned destruction
+        let t_1 = waiter.join().unwrap();
+        assert!(t_1.is_destroyed());
+        assert_eq!(notified.load(Ordering::SeqCst), 1);
+
+        // Don't panic on drop
+        drop(t_1);
+        t.clear_poison();
+        assert_eq!(t.reset(), Ok(()));
+        t.transfer_inner();
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn test_poisoned_by_destroy_panic() {
+        use std::panic::{self, AssertUnwindSafe};
+
+        let mut t = AtomicDestructor::new(InternalTestingPanic { abort: false });
+        assert!(panic::catch_unwind(AssertUnwindSafe(|| t.try_destroy())).is_err());
+        assert!(t.is_destroyed());
+        assert!(t.is_poisoned());
+
+        assert_eq!(t.reset(), Err(ResetError::Poisoned));
+        assert!(t.is_destroyed());
+
+        t.clear_poison();
+        assert!(!t.is_poisoned());
+        assert_eq!(t.reset(), Ok(()));
+        assert!(!t.is_destroyed());
+
+        // Don't panic on drop
+        t.transfer_inner();
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn test_abort_on_destroy_panic() {
+        const CHILD: &str = "ATOMIC_DESTRUCTOR_TEST_ABORT_CHILD";
+
+        if std::env::var_os(CHILD).is_some() {
+            let t = AtomicDestructor::new(InternalTestingPanic { abort: true });
+            drop(t);
+            return;
+        }
+
+        // Run this test in a child process, that must abort
+        let status = std::process::Command::new(std::env::current_exe().unwrap())
+            .args([
+                "--exact",
+                "tests::test_abort_on_destroy_panic",
+                "--test-threads=1",
+            ])
+            .env(CHILD, "1")
+            .stdout(std::process::Stdio::null())
+            .stderr(std::process::Stdio::null())
+            .status()
+            .unwrap();
+        assert!(!status.success());
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::process::ExitStatusExt;
+            assert_eq!(status.signal(), Some(6)); // SIGABRT
+        }
+    }
+
+    #[derive(Debug, Clone, Default)]
+    #[cfg(feature = "std")]
+    struct InternalTestingStats {
+        stats: Arc<std::sync::Mutex<Option<DestroyStats>>>,
+    }
+
+    #[cfg(feature = "std")]
+    impl AtomicDestroyer for InternalTestingStats {
+        fn on_destroy(&self) {}
+
+        fn on_destroy_with_stats(&self, stats: DestroyStats) {
+            *self.stats.lock().unwrap() = Some(stats);
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn test_on_destroy_with_stats() {
+        let inner = InternalTestingStats::default();
+        let t = AtomicDestructor::new(inner.clone());
+
+        // Churn
+        for _ in 0..3 {
+            let clones = t.clone_n(3);
+            let stealth = t.stealth_clone();
+            AtomicDestructor::drop_all(clones);
+            drop(stealth);
+        }
+        let t_1 = t.clone();
+        drop(t);
+        drop(t_1);
+
+        assert_eq!(
+            *inner.stats.lock().unwrap(),
+            Some(DestroyStats {
+                total_clones: 10,
+                high_water: 4,
+                saturated: false,
+            })
+        );
+    }
+
+    #[derive(Debug, Clone, Default)]
+    #[cfg(feature = "std")]
+    struct InternalTestingDrops {
+        events: Arc<std::sync::Mutex<Vec<String>>>,
+    }
+
+    #[cfg(feature = "std")]
+    impl AtomicDestroyer for InternalTestingDrops {
+        fn on_any_drop(&self, remaining: usize) {
+            self.events
+                .lock()
+                .unwrap()
+                .push(format!("drop:{}", remaining));
+        }
+
+        fn on_destroy(&self) {
+            self.events.lock().unwrap().push(String::from("destroy"));
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn test_on_any_drop() {
+        let inner = InternalTestingDrops::default();
+        let t = AtomicDestructor::new(inner.clone());
+        let t_1 = t.clone();
+        let t_2 = t.clone();
+        let stealth = t.stealth_clone();
+
+        drop(t_1);
+        drop(stealth);
+        drop(t);
+        drop(t_2);
+        assert_eq!(
+            *inner.events.lock().unwrap(),
+            vec!["drop:2", "drop:1", "drop:0", "destroy"]
+        );
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic(expected = "destroyed while 1 stealth instances are alive")]
+    fn test_forbid_stealth_during_destroy() {
+        let t = AtomicDestructor::builder(InternalTestingStealth)
+            .forbid_stealth_during_destroy(true)
+            .build();
+        let _observer = t.stealth_clone();
+        drop(t);
+    }
+
+    #[test]
+    fn test_forbid_stealth_during_destroy_no_observers() {
+        let inner = CountingDestroyer::new();
+        let destroyed = inner.destroyed_counter();
+        let t = AtomicDestructor::builder(inner.clone())
+            .forbid_stealth_during_destroy(true)
+            .build();
+        let t_1 = t.clone();
+
+        // The stealth instance held by the guard isn't counted
+        let guard = t.destroy_guard();
+        let observer = t.stealth_clone();
+        drop(observer);
+        drop(t);
+        drop(t_1);
+        drop(guard);
+        assert_eq!(destroyed.load(Ordering::SeqCst), 1);
+
+        // Not enabled
+        let t = AtomicDestructor::new(InternalTestingStealth);
+        let observer = t.stealth_clone();
+        drop(t);
+        assert!(observer.is_destroyed());
+    }
+
+    #[test]
+    fn test_has_stealth_observers() {
+        let t = AtomicDestructor::new(InternalTestingStealth);
+        let t_1 = t.clone();
+        assert!(!t.has_stealth_observers());
+
+        let stealth = t_1.stealth_clone();
+        assert!(t.has_stealth_observers());
+        assert!(stealth.has_stealth_observers());
+
+        drop(stealth);
+        assert!(!t.has_stealth_observers());
     }
 }
@@ -0,0 +1,84 @@
+// Copyright (c) 2024 Yuki Kishimoto
+// Distributed under the MIT software license
+
+//! Compact lifecycle display
+
+use core::fmt;
+
+use crate::{AtomicDestroyer, AtomicDestructor};
+
+/// Compact lifecycle display, terser than [`Debug`](fmt::Debug)
+///
+/// Format as `[alive:<counter>]`, with an `s` suffix if stealth (i.e., `[alive:3s]`), or `[destroyed]`.
+///
+/// Returned by [`AtomicDestructor::fmt_lifecycle`].
+pub struct LifecycleDisplay<'a, T>
+where
+    T: AtomicDestroyer,
+{
+    destructor: &'a AtomicDestructor<T>,
+}
+
+impl<'a, T> fmt::Debug for LifecycleDisplay<'a, T>
+where
+    T: AtomicDestroyer,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("LifecycleDisplay")
+            .field(&format_args!("{}", self))
+            .finish()
+    }
+}
+
+impl<'a, T> fmt::Display for LifecycleDisplay<'a, T>
+where
+    T: AtomicDestroyer,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (counter, destroyed) = self.destructor.load_consistent();
+
+        if destroyed {
+            return write!(f, "[destroyed]");
+        }
+
+        if self.destructor.is_stealth() {
+            write!(f, "[alive:{}s]", counter)
+        } else {
+            write!(f, "[alive:{}]", counter)
+        }
+    }
+}
+
+impl<'a, T> LifecycleDisplay<'a, T>
+where
+    T: AtomicDestroyer,
+{
+    pub(crate) fn new(destructor: &'a AtomicDestructor<T>) -> Self {
+        Self { destructor }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::string::ToString;
+
+    use super::*;
+    use crate::{NoopDestroyer, StealthClone};
+
+    #[test]
+    fn test_fmt_lifecycle() {
+        let t = AtomicDestructor::new(NoopDestroyer(()));
+        let t_1 = t.clone();
+        let t_2 = t.clone();
+        let stealth = t.stealth_clone();
+        assert_eq!(t.fmt_lifecycle().to_string(), "[alive:3]");
+        assert_eq!(stealth.fmt_lifecycle().to_string(), "[alive:3s]");
+
+        drop(t);
+        drop(t_1);
+        assert_eq!(stealth.fmt_lifecycle().to_string(), "[alive:1s]");
+
+        drop(t_2);
+        assert_eq!(stealth.fmt_lifecycle().to_string(), "[destroyed]");
+    }
+}
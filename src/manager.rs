@@ -0,0 +1,201 @@
+// Copyright (c) 2024 Yuki Kishimoto
+// Distributed under the MIT software license
+
+//! Destructor manager
+
+use core::fmt;
+use core::sync::atomic::Ordering;
+
+use crate::{AtomicDestroyer, AtomicDestructor};
+
+/// Destructor manager status
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct DestructorManagerStatus {
+    /// Number of non-stealth instances alive (the manager included)
+    pub active_count: usize,
+    /// Destroyed
+    pub destroyed: bool,
+    /// Sealed: no new clones can be spawned
+    pub sealed: bool,
+}
+
+/// Destructor manager
+///
+/// Single entry point to spawn clones, monitor and shut down an [`AtomicDestructor`].
+///
+/// The manager owns a non-stealth instance, so it keeps the inner alive until dropped or [force destroyed](DestructorManager::force_destroy).
+///
+/// # Example
+///
+/// ```rust
+/// use atomic_destructor::{AtomicDestroyer, AtomicDestructor};
+///
+/// #[derive(Debug, Clone)]
+/// struct Connection;
+///
+/// impl AtomicDestroyer for Connection {
+///     fn on_destroy(&self) {
+///         // Close connection
+///     }
+/// }
+///
+/// let manager = AtomicDestructor::new(Connection).into_managed();
+///
+/// // Hand out clones
+/// let worker = manager.spawn_clone().unwrap();
+/// assert_eq!(manager.active_count(), 2);
+///
+/// // Stop handing out clones
+/// manager.seal();
+/// assert!(manager.spawn_clone().is_none());
+///
+/// // Shutdown
+/// assert!(manager.force_destroy());
+/// assert!(worker.is_destroyed());
+/// assert!(manager.status().destroyed);
+/// ```
+pub struct DestructorManager<T>
+where
+    T: AtomicDestroyer,
+{
+    destructor: AtomicDestructor<T>,
+}
+
+impl<T> fmt::Debug for DestructorManager<T>
+where
+    T: AtomicDestroyer,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("DestructorManager")
+            .field("destructor", &self.destructor)
+            .field("sealed", &self.is_sealed())
+            .finish()
+    }
+}
+
+impl<T> DestructorManager<T>
+where
+    T: AtomicDestroyer,
+{
+    pub(crate) fn new(destructor: AtomicDestructor<T>) -> Self {
+        Self { destructor }
+    }
+
+    /// Spawn a new clone
+    ///
+    /// Return `None` if sealed or if the [clone limit](AtomicDestructor::clone_limit) is reached.
+    pub fn spawn_clone(&self) -> Option<AtomicDestructor<T>> {
+        self.destructor.try_clone()
+    }
+
+    /// Number of non-stealth instances alive (the manager included)
+    pub fn active_count(&self) -> usize {
+        self.destructor.counter()
+    }
+
+    /// Check if sealed
+    pub fn is_sealed(&self) -> bool {
+        self.destructor.state.sealed.load(Ordering::SeqCst)
+    }
+
+    /// Seal: no new non-stealth instance can be created from any instance of the lifecycle
+    ///
+    /// [`DestructorManager::spawn_clone`] and [`AtomicDestructor::try_clone`] return `None`, `clone` panics.
+    /// Already spawned clones stay alive, and stealth clones are still allowed.
+    pub fn seal(&self) {
+        self.destructor.state.sealed.store(true, Ordering::SeqCst);
+    }
+
+    /// Unseal
+    pub fn unseal(&self) {
+        self.destructor.state.sealed.store(false, Ordering::SeqCst);
+    }
+
+    /// Destroy now, regardless of the instances still alive
+    ///
+    /// The outstanding instances are marked as destroyed
+    /// and [`AtomicDestroyer::on_destroy`] is not called again when they are dropped.
+    ///
+    /// Return `true` if this call ran the destruction, `false` if already destroyed.
+    pub fn force_destroy(&self) -> bool {
+        self.destructor.destroy()
+    }
+
+    /// Get status
+    pub fn status(&self) -> DestructorManagerStatus {
+        DestructorManagerStatus {
+            active_count: self.active_count(),
+            destroyed: self.destructor.is_destroyed(),
+            sealed: self.is_sealed(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::CountingDestroyer;
+    use crate::StealthClone;
+
+    #[test]
+    fn test_manager() {
+        let inner = CountingDestroyer::new();
+        let manager = AtomicDestructor::new(inner.clone()).into_managed();
+        assert_eq!(
+            manager.status(),
+            DestructorManagerStatus {
+                active_count: 1,
+                destroyed: false,
+                sealed: false,
+            }
+        );
+
+        let t_1 = manager.spawn_clone().unwrap();
+        assert_eq!(manager.active_count(), 2);
+
+        manager.seal();
+        assert!(manager.is_sealed());
+        assert!(manager.spawn_clone().is_none());
+
+        // Sealed for the clones handed out before too
+        assert!(t_1.try_clone().is_none());
+        assert!(t_1.stealth_clone().try_clone().is_none());
+        assert_eq!(manager.active_count(), 2);
+
+        manager.unseal();
+        let t_2 = manager.spawn_clone().unwrap();
+        assert_eq!(manager.active_count(), 3);
+
+        drop(t_1);
+        drop(t_2);
+        assert_eq!(manager.active_count(), 1);
+
+        drop(manager);
+        assert_eq!(inner.destroyed(), 1);
+    }
+
+    #[test]
+    #[should_panic(expected = "lifecycle sealed")]
+    fn test_manager_sealed_clone() {
+        let manager = AtomicDestructor::new(CountingDestroyer::new()).into_managed();
+        let t_1 = manager.spawn_clone().unwrap();
+        manager.seal();
+        let _t_2 = t_1.clone();
+    }
+
+    #[test]
+    fn test_manager_force_destroy() {
+        let inner = CountingDestroyer::new();
+        let manager = AtomicDestructor::new(inner.clone()).into_managed();
+        let t_1 = manager.spawn_clone().unwrap();
+
+        assert!(manager.force_destroy());
+        assert!(!manager.force_destroy());
+        assert!(t_1.is_destroyed());
+        assert_eq!(inner.destroyed(), 1);
+
+        drop(t_1);
+        drop(manager);
+        assert_eq!(inner.destroyed(), 1);
+    }
+}
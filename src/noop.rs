@@ -0,0 +1,52 @@
+// Copyright (c) 2024 Yuki Kishimoto
+// Distributed under the MIT software license
+
+//! No-op destroyer
+
+use core::fmt::Debug;
+use core::ops::{Deref, DerefMut};
+
+use crate::AtomicDestroyer;
+
+/// Destroyer that does nothing on destruction
+///
+/// Wraps any `Debug + Clone` value, i.e. for tests or placeholders.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct NoopDestroyer<T>(pub T);
+
+impl<T> Deref for NoopDestroyer<T> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<T> DerefMut for NoopDestroyer<T> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
+
+impl<T> AtomicDestroyer for NoopDestroyer<T>
+where
+    T: Debug + Clone,
+{
+    fn on_destroy(&self) {}
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{AtomicDestructor, StealthClone};
+
+    #[test]
+    fn test_noop_destroyer() {
+        let t = AtomicDestructor::new(NoopDestroyer("value"));
+        let stealth = t.stealth_clone();
+        assert_eq!(**t, "value");
+
+        drop(t);
+        assert!(stealth.is_destroyed());
+    }
+}
@@ -0,0 +1,89 @@
+// Copyright (c) 2024 Yuki Kishimoto
+// Distributed under the MIT software license
+
+//! Observer
+
+use core::fmt;
+
+use crate::{AtomicDestroyer, AtomicDestructor, StealthClone};
+
+/// Observer that neither keeps the destructor alive nor participates in the counter
+///
+/// The inner can be accessed only while not destroyed.
+///
+/// Returned by [`AtomicDestructor::as_observer_weak`].
+pub struct ObserverWeak<T>
+where
+    T: AtomicDestroyer,
+{
+    destructor: AtomicDestructor<T>,
+}
+
+impl<T> Clone for ObserverWeak<T>
+where
+    T: AtomicDestroyer,
+{
+    fn clone(&self) -> Self {
+        Self {
+            destructor: self.destructor.stealth_clone(),
+        }
+    }
+}
+
+impl<T> fmt::Debug for ObserverWeak<T>
+where
+    T: AtomicDestroyer,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ObserverWeak")
+            .field("alive", &self.is_alive())
+            .finish()
+    }
+}
+
+impl<T> ObserverWeak<T>
+where
+    T: AtomicDestroyer,
+{
+    pub(crate) fn new(destructor: &AtomicDestructor<T>) -> Self {
+        Self {
+            destructor: destructor.stealth_clone(),
+        }
+    }
+
+    /// Check if the observed destructor is NOT destroyed
+    pub fn is_alive(&self) -> bool {
+        !self.destructor.is_destroyed()
+    }
+
+    /// Get the inner, if not destroyed
+    ///
+    /// Return `None` if destroyed.
+    pub fn peek(&self) -> Option<&T> {
+        self.destructor.peek()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::NoopDestroyer;
+
+    #[test]
+    fn test_observer_weak() {
+        let t = AtomicDestructor::new(NoopDestroyer(1));
+        let observer = t.as_observer_weak();
+        let observer_1 = observer.clone();
+        assert_eq!(t.counter(), 1);
+
+        assert!(observer.is_alive());
+        assert_eq!(observer.peek().map(|i| i.0), Some(1));
+
+        // The observer doesn't keep the destructor alive
+        drop(t);
+        assert!(!observer.is_alive());
+        assert!(observer.peek().is_none());
+        assert!(!observer_1.is_alive());
+        assert!(observer_1.peek().is_none());
+    }
+}
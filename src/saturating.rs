@@ -1,52 +1,272 @@
 // Copyright (c) 2024 Yuki Kishimoto
 // Distributed under the MIT software license
 
-use core::sync::atomic::{AtomicUsize, Ordering};
+use core::sync::atomic::{AtomicU16, AtomicU8, AtomicUsize, Ordering};
 
-pub trait SaturatingUsize {
-    fn saturating_increment(&self, order: Ordering) -> usize;
+pub trait SaturatingInt {
+    type Int;
 
-    fn saturating_decrement(&self, order: Ordering) -> usize;
+    fn saturating_increment(&self, order: Ordering) -> Self::Int;
+
+    fn saturating_decrement(&self, order: Ordering) -> Self::Int;
+
+    fn saturating_increment_prev(&self, order: Ordering) -> (Self::Int, Self::Int);
+
+    fn saturating_decrement_prev(&self, order: Ordering) -> (Self::Int, Self::Int);
+
+    fn saturating_add(&self, delta: Self::Int, order: Ordering) -> Self::Int;
+
+    fn saturating_sub(&self, delta: Self::Int, order: Ordering) -> Self::Int;
+
+    fn saturating_add_prev(&self, delta: Self::Int, order: Ordering) -> (Self::Int, Self::Int);
+
+    fn saturating_sub_prev(&self, delta: Self::Int, order: Ordering) -> (Self::Int, Self::Int);
+
+    fn checked_add(&self, delta: Self::Int, max: Self::Int, order: Ordering) -> Option<Self::Int>;
 }
 
-impl SaturatingUsize for AtomicUsize {
-    /// Atomically increments the AtomicUsize by 1, saturating at `usize::MAX`.
-    ///
-    /// Return the new value or `usize::MAX`.
-    fn saturating_increment(&self, order: Ordering) -> usize {
-        loop {
-            let current: usize = self.load(order);
+/// Implement [`SaturatingInt`] for an atomic unsigned integer
+macro_rules! impl_saturating_int {
+    ($atomic:ty, $int:ty) => {
+        impl SaturatingInt for $atomic {
+            type Int = $int;
 
-            if current == usize::MAX {
-                // Already at maximum, cannot increment further
-                return current;
+            /// Atomically increments by 1, saturating at the max value.
+            ///
+            /// Return the new value or the max value.
+            #[inline]
+            fn saturating_increment(&self, order: Ordering) -> $int {
+                self.saturating_increment_prev(order).1
             }
 
-            let new: usize = current + 1;
-            match self.compare_exchange(current, new, order, order) {
-                Ok(_) => return new,
-                Err(_) => continue, // Retry if the value changed concurrently
+            /// Atomically decrements by 1, saturating at `0`.
+            ///
+            /// Return the new value or `0`.
+            #[inline]
+            fn saturating_decrement(&self, order: Ordering) -> $int {
+                self.saturating_sub(1, order)
             }
-        }
-    }
 
-    /// Atomically decrements the AtomicUsize by 1, saturating at `0`.
-    ///
-    /// Return the new value or `0`.
-    fn saturating_decrement(&self, order: Ordering) -> usize {
-        loop {
-            let current: usize = self.load(order);
+            /// Atomically increments by 1, saturating at the max value.
+            ///
+            /// Return the old and the new value (i.e., to detect the `1 -> 2` transition).
+            #[inline]
+            fn saturating_increment_prev(&self, order: Ordering) -> ($int, $int) {
+                self.saturating_add_prev(1, order)
+            }
+
+            /// Atomically decrements by 1, saturating at `0`.
+            ///
+            /// Return the old and the new value (i.e., to detect the `1 -> 0` transition).
+            #[inline]
+            fn saturating_decrement_prev(&self, order: Ordering) -> ($int, $int) {
+                self.saturating_sub_prev(1, order)
+            }
 
-            if current == 0 {
-                // Already at minimum, cannot decrement further
-                return current;
+            /// Atomically adds `delta`, saturating at the max value.
+            ///
+            /// Return the new value or the max value.
+            #[inline]
+            fn saturating_add(&self, delta: $int, order: Ordering) -> $int {
+                self.saturating_add_prev(delta, order).1
             }
 
-            let new: usize = current - 1;
-            match self.compare_exchange(current, new, order, order) {
-                Ok(_) => return new,
-                Err(_) => continue, // Retry if the value changed concurrently
+            /// Atomically subtracts `delta`, saturating at `0`.
+            ///
+            /// Return the new value or `0`.
+            #[inline]
+            fn saturating_sub(&self, delta: $int, order: Ordering) -> $int {
+                self.saturating_sub_prev(delta, order).1
+            }
+
+            /// Atomically adds `delta`, saturating at the max value.
+            ///
+            /// Return the old and the new value.
+            #[inline]
+            fn saturating_add_prev(&self, delta: $int, order: Ordering) -> ($int, $int) {
+                loop {
+                    let current: $int = self.load(order);
+
+                    if current == <$int>::MAX {
+                        // Already at maximum, cannot increment further
+                        return (current, current);
+                    }
+
+                    let new: $int = current.saturating_add(delta);
+                    match self.compare_exchange(current, new, order, order) {
+                        Ok(_) => return (current, new),
+                        Err(_) => continue, // Retry if the value changed concurrently
+                    }
+                }
+            }
+
+            /// Atomically subtracts `delta`, saturating at `0`.
+            ///
+            /// Return the old and the new value.
+            #[inline]
+            fn saturating_sub_prev(&self, delta: $int, order: Ordering) -> ($int, $int) {
+                loop {
+                    let current: $int = self.load(order);
+
+                    if current == 0 {
+                        // Already at minimum, cannot decrement further
+                        return (current, current);
+                    }
+
+                    let new: $int = current.saturating_sub(delta);
+                    match self.compare_exchange(current, new, order, order) {
+                        Ok(_) => return (current, new),
+                        Err(_) => continue, // Retry if the value changed concurrently
+                    }
+                }
+            }
+
+            /// Atomically adds `delta`, only if the result doesn't exceed `max`.
+            ///
+            /// Return the new value or `None`.
+            fn checked_add(&self, delta: $int, max: $int, order: Ordering) -> Option<$int> {
+                loop {
+                    let current: $int = self.load(order);
+
+                    let new: $int = match current.checked_add(delta) {
+                        Some(new) if new <= max => new,
+                        // Limit reached, cannot increment further
+                        _ => return None,
+                    };
+
+                    match self.compare_exchange(current, new, order, order) {
+                        Ok(_) => return Some(new),
+                        Err(_) => continue, // Retry if the value changed concurrently
+                    }
+                }
             }
         }
+    };
+}
+
+impl_saturating_int!(AtomicUsize, usize);
+impl_saturating_int!(AtomicU16, u16);
+impl_saturating_int!(AtomicU8, u8);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_saturating_add() {
+        let value = AtomicUsize::new(1);
+        assert_eq!(value.saturating_add(2, Ordering::SeqCst), 3);
+        assert_eq!(value.saturating_increment(Ordering::SeqCst), 4);
+
+        let value = AtomicUsize::new(usize::MAX - 1);
+        assert_eq!(value.saturating_add(5, Ordering::SeqCst), usize::MAX);
+        assert_eq!(value.saturating_increment(Ordering::SeqCst), usize::MAX);
+    }
+
+    #[test]
+    fn test_saturating_sub() {
+        let value = AtomicUsize::new(5);
+        assert_eq!(value.saturating_sub(2, Ordering::SeqCst), 3);
+        assert_eq!(value.saturating_decrement(Ordering::SeqCst), 2);
+
+        assert_eq!(value.saturating_sub(10, Ordering::SeqCst), 0);
+        assert_eq!(value.saturating_decrement(Ordering::SeqCst), 0);
+        assert_eq!(value.load(Ordering::SeqCst), 0);
+    }
+
+    #[test]
+    fn test_saturating_boundaries() {
+        for delta in [0, 1, 2, usize::MAX - 1, usize::MAX] {
+            let value = AtomicUsize::new(usize::MAX);
+            assert_eq!(value.saturating_add(delta, Ordering::SeqCst), usize::MAX);
+
+            let value = AtomicUsize::new(0);
+            assert_eq!(value.saturating_sub(delta, Ordering::SeqCst), 0);
+
+            let value = AtomicUsize::new(usize::MAX - 1);
+            let expected = if delta == 0 {
+                usize::MAX - 1
+            } else {
+                usize::MAX
+            };
+            assert_eq!(value.saturating_add(delta, Ordering::SeqCst), expected);
+
+            let value = AtomicUsize::new(1);
+            let expected = if delta == 0 { 1 } else { 0 };
+            assert_eq!(value.saturating_sub(delta, Ordering::SeqCst), expected);
+        }
+
+        // Exactly reaching the boundaries
+        let value = AtomicUsize::new(10);
+        assert_eq!(
+            value.saturating_add(usize::MAX - 10, Ordering::SeqCst),
+            usize::MAX
+        );
+        assert_eq!(value.saturating_sub(usize::MAX, Ordering::SeqCst), 0);
+    }
+
+    #[test]
+    fn test_saturating_prev() {
+        let value = AtomicUsize::new(1);
+        assert_eq!(value.saturating_increment_prev(Ordering::SeqCst), (1, 2));
+        assert_eq!(value.saturating_decrement_prev(Ordering::SeqCst), (2, 1));
+        assert_eq!(value.saturating_decrement_prev(Ordering::SeqCst), (1, 0));
+        assert_eq!(value.saturating_decrement_prev(Ordering::SeqCst), (0, 0));
+        assert_eq!(value.saturating_increment_prev(Ordering::SeqCst), (0, 1));
+
+        let value = AtomicUsize::new(usize::MAX - 1);
+        assert_eq!(
+            value.saturating_increment_prev(Ordering::SeqCst),
+            (usize::MAX - 1, usize::MAX)
+        );
+        assert_eq!(
+            value.saturating_increment_prev(Ordering::SeqCst),
+            (usize::MAX, usize::MAX)
+        );
+        assert_eq!(
+            value.saturating_decrement_prev(Ordering::SeqCst),
+            (usize::MAX, usize::MAX - 1)
+        );
+
+        let value = AtomicU8::new(u8::MAX);
+        assert_eq!(
+            value.saturating_increment_prev(Ordering::SeqCst),
+            (u8::MAX, u8::MAX)
+        );
+        assert_eq!(
+            value.saturating_sub_prev(u8::MAX, Ordering::SeqCst),
+            (u8::MAX, 0)
+        );
+        assert_eq!(value.saturating_add_prev(3, Ordering::SeqCst), (0, 3));
+    }
+
+    #[test]
+    fn test_checked_add() {
+        let value = AtomicUsize::new(1);
+        assert_eq!(value.checked_add(2, 3, Ordering::SeqCst), Some(3));
+        assert_eq!(value.checked_add(1, 3, Ordering::SeqCst), None);
+        assert_eq!(
+            value.checked_add(usize::MAX, usize::MAX, Ordering::SeqCst),
+            None
+        );
+        assert_eq!(value.load(Ordering::SeqCst), 3);
+    }
+
+    #[test]
+    fn test_saturating_small_width() {
+        let value = AtomicU8::new(u8::MAX - 2);
+        assert_eq!(value.saturating_increment(Ordering::SeqCst), u8::MAX - 1);
+        assert_eq!(value.saturating_add(10, Ordering::SeqCst), u8::MAX);
+        assert_eq!(value.saturating_increment(Ordering::SeqCst), u8::MAX);
+        assert_eq!(value.checked_add(1, u8::MAX, Ordering::SeqCst), None);
+        assert_eq!(value.saturating_sub(u8::MAX, Ordering::SeqCst), 0);
+        assert_eq!(value.saturating_decrement(Ordering::SeqCst), 0);
+
+        let value = AtomicU16::new(u16::MAX - 1);
+        assert_eq!(value.saturating_increment(Ordering::SeqCst), u16::MAX);
+        assert_eq!(value.saturating_increment(Ordering::SeqCst), u16::MAX);
+        assert_eq!(value.saturating_sub(u16::MAX - 1, Ordering::SeqCst), 1);
+        assert_eq!(value.checked_add(2, 3, Ordering::SeqCst), Some(3));
+        assert_eq!(value.saturating_sub(5, Ordering::SeqCst), 0);
     }
 }
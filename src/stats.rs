@@ -0,0 +1,191 @@
+// Copyright (c) 2024 Yuki Kishimoto
+// Distributed under the MIT software license
+
+//! Lifetime statistics
+
+use alloc::sync::Arc;
+use core::fmt;
+use core::sync::atomic::{AtomicUsize, Ordering};
+use core::time::Duration;
+use std::time::Instant;
+
+use crate::sync::{self, Mutex};
+use crate::{AtomicDestroyer, AtomicDestructor, State};
+
+#[derive(Debug)]
+struct LifetimeStatsInner {
+    created_at: Instant,
+    peak_clone_count: AtomicUsize,
+    total_clone_events: AtomicUsize,
+    total_drop_events: AtomicUsize,
+    destroyed_at: Mutex<Option<Instant>>,
+}
+
+/// Lifetime statistics of a lifecycle, for capacity planning
+///
+/// Updated by all the instances sharing the lifecycle. Cheap to clone.
+///
+/// Created with [`AtomicDestructor::with_stats`].
+#[derive(Debug, Clone)]
+pub struct LifetimeStats {
+    inner: Arc<LifetimeStatsInner>,
+}
+
+impl fmt::Display for LifetimeStats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.destroyed_at() {
+            Some(destroyed_at) => write!(
+                f,
+                "destroyed after {:?}",
+                destroyed_at.duration_since(self.created_at())
+            )?,
+            None => write!(f, "alive for {:?}", self.lifetime())?,
+        }
+
+        write!(
+            f,
+            ", peak clones: {}, clones: {}, drops: {}",
+            self.peak_clone_count(),
+            self.total_clone_events(),
+            self.total_drop_events()
+        )
+    }
+}
+
+impl LifetimeStats {
+    fn new() -> Self {
+        Self {
+            inner: Arc::new(LifetimeStatsInner {
+                created_at: Instant::now(),
+                peak_clone_count: AtomicUsize::new(1),
+                total_clone_events: AtomicUsize::new(0),
+                total_drop_events: AtomicUsize::new(0),
+                destroyed_at: Mutex::new(None),
+            }),
+        }
+    }
+
+    /// When the lifecycle was created
+    pub fn created_at(&self) -> Instant {
+        self.inner.created_at
+    }
+
+    /// Max number of non-stealth instances alive at the same time
+    pub fn peak_clone_count(&self) -> usize {
+        self.inner.peak_clone_count.load(Ordering::SeqCst)
+    }
+
+    /// Number of non-stealth clones created
+    pub fn total_clone_events(&self) -> usize {
+        self.inner.total_clone_events.load(Ordering::SeqCst)
+    }
+
+    /// Number of non-stealth instances dropped
+    pub fn total_drop_events(&self) -> usize {
+        self.inner.total_drop_events.load(Ordering::SeqCst)
+    }
+
+    /// When the lifecycle was destroyed, if destroyed
+    pub fn destroyed_at(&self) -> Option<Instant> {
+        *sync::lock(&self.inner.destroyed_at)
+    }
+
+    /// Time elapsed from the creation to the destruction, or to now if not destroyed yet
+    pub fn lifetime(&self) -> Duration {
+        match self.destroyed_at() {
+            Some(destroyed_at) => destroyed_at.duration_since(self.created_at()),
+            None => self.created_at().elapsed(),
+        }
+    }
+
+    /// `n` clones brought the counter to `counter`
+    pub(crate) fn record_clones(&self, n: usize, counter: usize) {
+        self.inner.total_clone_events.fetch_add(n, Ordering::SeqCst);
+        self.inner
+            .peak_clone_count
+            .fetch_max(counter, Ordering::SeqCst);
+    }
+
+    pub(crate) fn record_drop(&self) {
+        self.inner.total_drop_events.fetch_add(1, Ordering::SeqCst);
+    }
+
+    pub(crate) fn record_destroy(&self) {
+        *sync::lock(&self.inner.destroyed_at) = Some(Instant::now());
+    }
+}
+
+impl<T> AtomicDestructor<T>
+where
+    T: AtomicDestroyer,
+{
+    /// New wrapper collecting its [lifetime statistics](LifetimeStats)
+    pub fn with_stats(inner: T) -> (Self, LifetimeStats) {
+        let stats: LifetimeStats = LifetimeStats::new();
+
+        let mut state: State = State::new(1);
+        state.stats = Some(stats.clone());
+
+        let state: Arc<State> = Arc::new(state);
+        let destructor: Self = Self {
+            #[cfg(feature = "thread-tracking")]
+            thread: state.track_thread(1),
+            state,
+            stealth: false,
+            label: None,
+            generation: 0,
+            #[cfg(feature = "std")]
+            joined: false,
+            inner: Some(inner),
+        };
+
+        (destructor, stats)
+    }
+
+    /// Get the [lifetime statistics](LifetimeStats), if created with [`AtomicDestructor::with_stats`]
+    pub fn stats(&self) -> Option<LifetimeStats> {
+        self.state.stats.clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::string::ToString;
+
+    use super::*;
+    use crate::{NoopDestroyer, StealthClone};
+
+    #[test]
+    fn test_with_stats() {
+        let (t, stats) = AtomicDestructor::with_stats(NoopDestroyer(()));
+        assert!(t.stats().is_some());
+        assert!(AtomicDestructor::new(NoopDestroyer(())).stats().is_none());
+
+        let t_1 = t.clone();
+        let t_2 = t.clone();
+        drop(t_1);
+        let t_3 = t.clone();
+        let clones = t.clone_n(2);
+        let stealth = t.stealth_clone();
+        assert_eq!(stats.peak_clone_count(), 5);
+        assert_eq!(stats.total_clone_events(), 5);
+        assert_eq!(stats.total_drop_events(), 1);
+        assert!(stats.destroyed_at().is_none());
+        assert!(stats.to_string().starts_with("alive for "));
+
+        AtomicDestructor::drop_all(clones);
+        drop(t_2);
+        drop(t_3);
+        drop(stealth);
+        assert!(stats.destroyed_at().is_none());
+
+        drop(t);
+        assert_eq!(stats.total_drop_events(), 6);
+        assert_eq!(stats.peak_clone_count(), 5);
+        assert!(stats.destroyed_at().is_some());
+        assert_eq!(stats.lifetime(), stats.lifetime());
+        assert!(stats
+            .to_string()
+            .ends_with(", peak clones: 5, clones: 5, drops: 6"));
+    }
+}
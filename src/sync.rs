@@ -0,0 +1,80 @@
+// Copyright (c) 2024 Yuki Kishimoto
+// Distributed under the MIT software license
+
+//! Internal synchronization primitives
+//!
+//! Backed by `std::sync`, or by `parking_lot` with the `parking_lot` feature.
+
+#[cfg(feature = "parking_lot")]
+pub(crate) use parking_lot::{Condvar, Mutex, MutexGuard};
+#[cfg(not(feature = "parking_lot"))]
+use std::sync::PoisonError;
+#[cfg(not(feature = "parking_lot"))]
+pub(crate) use std::sync::{Condvar, Mutex, MutexGuard};
+
+/// Lock the mutex, ignoring poisoning
+///
+/// The guarded data is always left in a consistent state, so a panic while holding the lock can be ignored.
+#[cfg(not(feature = "parking_lot"))]
+pub(crate) fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+/// Lock the mutex
+#[cfg(feature = "parking_lot")]
+pub(crate) fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex.lock()
+}
+
+/// Wait on the condvar, ignoring poisoning
+#[cfg(not(feature = "parking_lot"))]
+pub(crate) fn wait<'a, T>(condvar: &Condvar, guard: MutexGuard<'a, T>) -> MutexGuard<'a, T> {
+    condvar.wait(guard).unwrap_or_else(PoisonError::into_inner)
+}
+
+/// Wait on the condvar
+#[cfg(feature = "parking_lot")]
+pub(crate) fn wait<'a, T>(condvar: &Condvar, mut guard: MutexGuard<'a, T>) -> MutexGuard<'a, T> {
+    condvar.wait(&mut guard);
+    guard
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::sync::Arc;
+    use std::thread;
+
+    use super::*;
+
+    #[test]
+    fn test_lock_after_panic() {
+        let mutex = Arc::new(Mutex::new(1));
+        let m = mutex.clone();
+        let res = thread::spawn(move || {
+            let _guard = lock(&m);
+            panic!("panic while holding the lock");
+        })
+        .join();
+        assert!(res.is_err());
+
+        *lock(&mutex) += 1;
+        assert_eq!(*lock(&mutex), 2);
+    }
+
+    #[test]
+    fn test_wait() {
+        let pair = Arc::new((Mutex::new(false), Condvar::new()));
+        let p = pair.clone();
+        let handle = thread::spawn(move || {
+            *lock(&p.0) = true;
+            p.1.notify_all();
+        });
+
+        let mut ready = lock(&pair.0);
+        while !*ready {
+            ready = wait(&pair.1, ready);
+        }
+        drop(ready);
+        handle.join().unwrap();
+    }
+}
@@ -0,0 +1,101 @@
+// Copyright (c) 2024 Yuki Kishimoto
+// Distributed under the MIT software license
+
+//! Lifecycle test fixtures
+
+use alloc::sync::Arc;
+use core::sync::atomic::{AtomicUsize, Ordering};
+
+use crate::AtomicDestroyer;
+
+/// Destroyer that counts its destructions
+///
+/// All the clones share the same count, retrieved with [`CountingDestroyer::destroyed_counter`]
+/// and checked with [`assert_destroyed_once`].
+#[derive(Debug, Clone, Default)]
+pub struct CountingDestroyer {
+    destroyed: Arc<AtomicUsize>,
+}
+
+impl AtomicDestroyer for CountingDestroyer {
+    fn on_destroy(&self) {
+        self.destroyed.fetch_add(1, Ordering::SeqCst);
+    }
+}
+
+impl CountingDestroyer {
+    /// New destroyer, never destroyed
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Get the shared count of destructions
+    pub fn destroyed_counter(&self) -> Arc<AtomicUsize> {
+        self.destroyed.clone()
+    }
+
+    /// Get how many times it has been destroyed
+    pub fn destroyed(&self) -> usize {
+        self.destroyed.load(Ordering::SeqCst)
+    }
+}
+
+/// Assert that a [`CountingDestroyer`] has been destroyed exactly once
+///
+/// # Panics
+///
+/// Panics if never destroyed or destroyed more than once.
+#[track_caller]
+pub fn assert_destroyed_once(counter: &AtomicUsize) {
+    let destroyed: usize = counter.load(Ordering::SeqCst);
+    assert!(
+        destroyed == 1,
+        "expected to be destroyed exactly once, destroyed {} times",
+        destroyed
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::AtomicDestructor;
+
+    #[test]
+    fn test_destroyed_once() {
+        let inner = CountingDestroyer::new();
+        let counter = inner.destroyed_counter();
+        let t = AtomicDestructor::new(inner);
+        let t_1 = t.clone();
+
+        drop(t);
+        assert_eq!(counter.load(Ordering::SeqCst), 0);
+
+        drop(t_1);
+        assert_destroyed_once(&counter);
+    }
+
+    #[test]
+    #[should_panic(expected = "destroyed 0 times")]
+    fn test_never_destroyed() {
+        let inner = CountingDestroyer::new();
+        let t = AtomicDestructor::new(inner.clone());
+        let _t_1 = t.clone();
+        drop(t);
+
+        assert_destroyed_once(&inner.destroyed_counter());
+    }
+
+    #[test]
+    #[should_panic(expected = "destroyed 2 times")]
+    fn test_double_destroy_caught() {
+        let t = AtomicDestructor::new(CountingDestroyer::new());
+        let counter = t.destroyed_counter();
+
+        // Bug: wrapping the inner again starts a second lifecycle, instead of cloning
+        let t_1 = AtomicDestructor::new(t.inner().clone());
+
+        drop(t);
+        drop(t_1);
+        assert_destroyed_once(&counter);
+    }
+}
@@ -0,0 +1,236 @@
+// Copyright (c) 2024 Yuki Kishimoto
+// Distributed under the MIT software license
+
+//! Timeout destroyer
+
+use alloc::boxed::Box;
+use alloc::format;
+use alloc::sync::Arc;
+use core::any::Any;
+use core::ops::{Deref, DerefMut};
+use core::sync::atomic::{AtomicBool, Ordering};
+use core::time::Duration;
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError, Sender};
+use std::thread;
+
+use crate::{AtomicDestroyer, AtomicDestructor, DestroyStats};
+
+type DestroyResult = Result<(), Box<dyn std::error::Error + Send + Sync>>;
+
+/// Outcome of the background destruction: the panic payload, if it panicked
+type WorkerResult = Result<DestroyResult, Box<dyn Any + Send>>;
+
+/// Destroyer that gives up waiting for a slow destruction
+///
+/// [`AtomicDestroyer::on_destroy`] of the inner runs in a background thread, and the dropping thread waits for it up to the timeout.
+/// If it takes longer, the destruction is considered done anyway: the destroyer is marked as [timed out](TimeoutDestroyer::timed_out),
+/// a timeout error is returned (see [`AtomicDestructor::last_destroy_error`]) and the background thread is abandoned.
+///
+/// A panic of the inner within the timeout is re-raised in the dropping thread.
+/// If [`AtomicDestroyer::abort_on_destroy_panic`], the background thread aborts the process, even after the timeout.
+///
+/// Created with [`AtomicDestructor::with_destroy_timeout`].
+#[derive(Debug, Clone)]
+pub struct TimeoutDestroyer<T> {
+    inner: T,
+    timeout: Duration,
+    timed_out: Arc<AtomicBool>,
+}
+
+impl<T> Deref for TimeoutDestroyer<T> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        &self.inner
+    }
+}
+
+impl<T> DerefMut for TimeoutDestroyer<T> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.inner
+    }
+}
+
+impl<T> TimeoutDestroyer<T> {
+    /// Get the timeout
+    pub fn timeout(&self) -> Duration {
+        self.timeout
+    }
+
+    /// Check if the destruction exceeded the timeout and was abandoned
+    pub fn timed_out(&self) -> bool {
+        self.timed_out.load(Ordering::SeqCst)
+    }
+}
+
+impl<T> AtomicDestroyer for TimeoutDestroyer<T>
+where
+    T: AtomicDestroyer + Send + 'static,
+{
+    #[cfg(feature = "tracing")]
+    fn name(&self) -> Option<alloc::string::String> {
+        self.inner.name()
+    }
+
+    fn clone_allowed(&self) -> bool {
+        self.inner.clone_allowed()
+    }
+
+    fn on_clone(&self, counter: usize) {
+        self.inner.on_clone(counter);
+    }
+
+    fn on_any_drop(&self, remaining: usize) {
+        self.inner.on_any_drop(remaining);
+    }
+
+    fn abort_on_destroy_panic(&self) -> bool {
+        self.inner.abort_on_destroy_panic()
+    }
+
+    fn on_destroy(&self) {
+        let _ = self.on_destroy_fallible(DestroyStats::default());
+    }
+
+    fn on_destroy_with_stats(&self, stats: DestroyStats) {
+        let _ = self.on_destroy_fallible(stats);
+    }
+
+    fn on_destroy_fallible(&self, stats: DestroyStats) -> DestroyResult {
+        let (tx, rx): (Sender<WorkerResult>, Receiver<WorkerResult>) = mpsc::channel();
+
+        let inner: T = self.inner.clone();
+        thread::spawn(move || {
+            // Aborts here if requested by the inner, otherwise the panic is sent back
+            let res: WorkerResult =
+                panic::catch_unwind(AssertUnwindSafe(|| crate::call_destroy_hook(&inner, stats)));
+
+            // The receiver may be already gone
+            let _ = tx.send(res);
+        });
+
+        match rx.recv_timeout(self.timeout) {
+            Ok(Ok(res)) => res,
+            Ok(Err(payload)) => panic::resume_unwind(payload),
+            Err(RecvTimeoutError::Timeout) => {
+                self.timed_out.store(true, Ordering::SeqCst);
+
+                #[cfg(feature = "tracing")]
+                match self.inner.name() {
+                    Some(name) => tracing::error!(
+                        "{} destruction exceeded {:?}, abandoned.",
+                        name,
+                        self.timeout
+                    ),
+                    None => tracing::error!("Destruction exceeded {:?}, abandoned.", self.timeout),
+                }
+
+                Err(format!("destruction timed out after {:?}", self.timeout).into())
+            }
+            Err(RecvTimeoutError::Disconnected) => Err("destruction panicked".into()),
+        }
+    }
+}
+
+impl<T> AtomicDestructor<TimeoutDestroyer<T>>
+where
+    T: AtomicDestroyer + Send + 'static,
+{
+    /// New wrapper that waits for the destruction of the inner up to `timeout`
+    ///
+    /// See [`TimeoutDestroyer`].
+    pub fn with_destroy_timeout(inner: T, timeout: Duration) -> Self {
+        Self::new(TimeoutDestroyer {
+            inner,
+            timeout,
+            timed_out: Arc::new(AtomicBool::new(false)),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sync::{self, Mutex};
+    use crate::StealthClone;
+
+    #[derive(Debug, Clone)]
+    struct Internal {
+        /// Blocks the destruction until a message is sent
+        release: Arc<Mutex<Receiver<()>>>,
+        destroyed: Sender<()>,
+    }
+
+    impl AtomicDestroyer for Internal {
+        fn on_destroy(&self) {
+            let _ = sync::lock(&self.release).recv();
+            let _ = self.destroyed.send(());
+        }
+    }
+
+    #[derive(Debug, Clone)]
+    struct InternalPanic;
+
+    impl AtomicDestroyer for InternalPanic {
+        fn on_destroy(&self) {
+            panic!("destroy failed");
+        }
+    }
+
+    fn internal() -> (Internal, Sender<()>, Receiver<()>) {
+        let (release_tx, release_rx) = mpsc::channel();
+        let (destroyed_tx, destroyed_rx) = mpsc::channel();
+        let inner = Internal {
+            release: Arc::new(Mutex::new(release_rx)),
+            destroyed: destroyed_tx,
+        };
+        (inner, release_tx, destroyed_rx)
+    }
+
+    #[test]
+    fn test_destroy_timeout_on_time() {
+        let (inner, release, destroyed) = internal();
+        let t = AtomicDestructor::with_destroy_timeout(inner, Duration::from_secs(60));
+        let observer = t.stealth_clone();
+        assert_eq!(observer.timeout(), Duration::from_secs(60));
+
+        release.send(()).unwrap();
+        drop(t);
+        assert!(observer.is_destroyed());
+        assert!(!observer.timed_out());
+        assert!(observer.last_destroy_error().is_none());
+        assert_eq!(destroyed.try_recv(), Ok(()));
+    }
+
+    #[test]
+    fn test_destroy_timeout_expired() {
+        let (inner, release, destroyed) = internal();
+        let t = AtomicDestructor::with_destroy_timeout(inner, Duration::from_millis(10));
+        let observer = t.stealth_clone();
+
+        // The destruction is blocked: returning proves that it was abandoned
+        drop(t);
+
+        assert!(observer.is_destroyed());
+        assert!(observer.timed_out());
+        assert!(observer.last_destroy_error().is_some());
+        assert!(destroyed.try_recv().is_err());
+
+        // The abandoned destruction completes in background
+        release.send(()).unwrap();
+        assert_eq!(destroyed.recv_timeout(Duration::from_secs(5)), Ok(()));
+    }
+
+    #[test]
+    fn test_destroy_timeout_panic() {
+        let t = AtomicDestructor::with_destroy_timeout(InternalPanic, Duration::from_secs(60));
+        let observer = t.stealth_clone();
+
+        // Re-raised in the dropping thread
+        assert!(panic::catch_unwind(AssertUnwindSafe(|| drop(t))).is_err());
+        assert!(observer.is_destroyed());
+        assert!(observer.is_poisoned());
+        assert!(!observer.timed_out());
+    }
+}
@@ -0,0 +1,276 @@
+// Copyright (c) 2024 Yuki Kishimoto
+// Distributed under the MIT software license
+
+//! Tokio integration
+
+#[cfg(feature = "tracing")]
+use alloc::string::String;
+use core::ops::{Deref, DerefMut};
+
+use tokio::runtime::Handle;
+
+use crate::{AtomicDestroyer, AtomicDestructor, DestroyStats};
+
+/// Destroyer that runs the destruction of the inner with [`tokio::task::spawn_blocking`]
+///
+/// Avoids blocking the runtime thread when the last instance is dropped from within an async task.
+///
+/// The blocking task is spawned on the handle set with [`AtomicDestructor::with_tokio_handle`] or, if not set,
+/// on the runtime of the dropping thread. Outside a runtime, the destruction is run inline.
+/// When spawned, an error returned by [`AtomicDestroyer::on_destroy_fallible`] of the inner can't be retrieved: it's only logged.
+#[derive(Debug, Clone)]
+pub struct SpawnBlockingWrapper<T>
+where
+    T: AtomicDestroyer,
+{
+    inner: T,
+    handle: Option<Handle>,
+}
+
+impl<T> Deref for SpawnBlockingWrapper<T>
+where
+    T: AtomicDestroyer,
+{
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        &self.inner
+    }
+}
+
+impl<T> DerefMut for SpawnBlockingWrapper<T>
+where
+    T: AtomicDestroyer,
+{
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.inner
+    }
+}
+
+impl<T> AtomicDestroyer for SpawnBlockingWrapper<T>
+where
+    T: AtomicDestroyer + Send + 'static,
+{
+    #[cfg(feature = "tracing")]
+    fn name(&self) -> Option<String> {
+        self.inner.name()
+    }
+
+    fn clone_allowed(&self) -> bool {
+        self.inner.clone_allowed()
+    }
+
+    fn on_clone(&self, counter: usize) {
+        self.inner.on_clone(counter);
+    }
+
+    fn on_any_drop(&self, remaining: usize) {
+        self.inner.on_any_drop(remaining);
+    }
+
+    #[cfg(feature = "std")]
+    fn abort_on_destroy_panic(&self) -> bool {
+        self.inner.abort_on_destroy_panic()
+    }
+
+    fn on_destroy(&self) {
+        self.on_destroy_with_stats(DestroyStats::default());
+    }
+
+    fn on_destroy_with_stats(&self, stats: DestroyStats) {
+        match self.runtime() {
+            Some(handle) => self.spawn_destroy(handle, stats),
+            None => self.inner.on_destroy_with_stats(stats),
+        }
+    }
+
+    #[cfg(feature = "std")]
+    fn on_destroy_fallible(
+        &self,
+        stats: DestroyStats,
+    ) -> Result<(), alloc::boxed::Box<dyn std::error::Error + Send + Sync>> {
+        match self.runtime() {
+            Some(handle) => {
+                self.spawn_destroy(handle, stats);
+                Ok(())
+            }
+            None => self.inner.on_destroy_fallible(stats),
+        }
+    }
+}
+
+impl<T> SpawnBlockingWrapper<T>
+where
+    T: AtomicDestroyer + Send + 'static,
+{
+    /// Runtime to spawn the destruction on, if any
+    fn runtime(&self) -> Option<Handle> {
+        self.handle.clone().or_else(|| Handle::try_current().ok())
+    }
+
+    fn spawn_destroy(&self, handle: Handle, stats: DestroyStats) {
+        let inner: T = self.inner.clone();
+        handle.spawn_blocking(move || crate::destroy_detached(&inner, stats));
+    }
+}
+
+impl<T> AtomicDestructor<T>
+where
+    T: AtomicDestroyer + Send + 'static,
+{
+    /// Run the destruction with [`tokio::task::spawn_blocking`]
+    ///
+    /// The new instance is counted like a clone before `self` is dropped, so it needs a free slot
+    /// if a [clone limit](AtomicDestructor::clone_limit) is set.
+    ///
+    /// Return `Err` with `self` if the clone is refused. See [`SpawnBlockingWrapper`].
+    pub fn on_destroy_spawn_blocking(
+        self,
+    ) -> Result<AtomicDestructor<SpawnBlockingWrapper<T>>, Self> {
+        let wrapper = SpawnBlockingWrapper {
+            inner: self.inner().clone(),
+            handle: None,
+        };
+        match self.map_view(wrapper) {
+            Some(view) => Ok(view),
+            None => Err(self),
+        }
+    }
+}
+
+impl<T> AtomicDestructor<SpawnBlockingWrapper<T>>
+where
+    T: AtomicDestroyer + Send + 'static,
+{
+    /// Set the runtime handle used to spawn the blocking destruction
+    ///
+    /// The handle is stored in this instance and inherited by the clones created after this call.
+    pub fn with_tokio_handle(mut self, handle: Handle) -> Self {
+        self.inner_mut().handle = Some(handle);
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::sync::Arc;
+    use core::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+    use std::time::{Duration, Instant};
+
+    use tokio::runtime::Builder;
+
+    use super::*;
+    use crate::DestroyTrigger;
+
+    #[derive(Debug, Clone, Default)]
+    struct Internal {
+        destroyed: Arc<AtomicBool>,
+    }
+
+    impl AtomicDestroyer for Internal {
+        fn on_destroy(&self) {
+            // Blocking cleanup
+            std::thread::sleep(Duration::from_millis(300));
+            self.destroyed.store(true, Ordering::SeqCst);
+        }
+    }
+
+    #[derive(Debug, Clone, Default)]
+    struct InternalStats {
+        high_water: Arc<AtomicUsize>,
+    }
+
+    impl AtomicDestroyer for InternalStats {
+        fn on_destroy(&self) {}
+
+        fn on_destroy_with_stats(&self, stats: DestroyStats) {
+            self.high_water.store(stats.high_water, Ordering::SeqCst);
+        }
+    }
+
+    #[test]
+    fn test_on_destroy_spawn_blocking() {
+        let rt = Builder::new_current_thread().build().unwrap();
+        let inner = Internal::default();
+
+        let t = AtomicDestructor::new(inner.clone())
+            .on_destroy_spawn_blocking()
+            .unwrap()
+            .with_tokio_handle(rt.handle().clone());
+        let t_1 = t.clone();
+        assert_eq!(t.counter(), 2);
+
+        rt.block_on(async move {
+            drop(t);
+
+            let now = Instant::now();
+            drop(t_1);
+
+            // The runtime thread isn't blocked by the cleanup
+            assert!(now.elapsed() < Duration::from_millis(300));
+            assert!(!inner.destroyed.load(Ordering::SeqCst));
+
+            while !inner.destroyed.load(Ordering::SeqCst) {
+                tokio::task::yield_now().await;
+            }
+        });
+    }
+
+    #[test]
+    fn test_on_destroy_spawn_blocking_outside_runtime() {
+        let inner = Internal::default();
+
+        let t = AtomicDestructor::new(inner.clone())
+            .on_destroy_spawn_blocking()
+            .unwrap();
+        drop(t);
+
+        // No runtime: run inline
+        assert!(inner.destroyed.load(Ordering::SeqCst));
+    }
+
+    #[test]
+    fn test_on_destroy_spawn_blocking_refused() {
+        let inner = Internal::default();
+
+        // No free slot for the new instance
+        let t = AtomicDestructor::builder(inner.clone())
+            .max_clones(1)
+            .build();
+        let t = t.on_destroy_spawn_blocking().unwrap_err();
+        assert_eq!(t.counter(), 1);
+        assert!(!t.is_destroyed());
+
+        // Already destroyed: the new instance doesn't resurrect the lifecycle
+        let t = AtomicDestructor::builder(Internal::default())
+            .destroy_trigger(DestroyTrigger::FirstDrop)
+            .build();
+        let t_1 = t.clone();
+        drop(t);
+        let view = t_1.on_destroy_spawn_blocking().unwrap();
+        assert!(view.is_destroyed());
+        assert_eq!(view.counter(), 1);
+    }
+
+    #[test]
+    fn test_on_destroy_spawn_blocking_with_stats() {
+        let rt = Builder::new_current_thread().build().unwrap();
+        let inner = InternalStats::default();
+
+        let t = AtomicDestructor::new(inner.clone())
+            .on_destroy_spawn_blocking()
+            .unwrap()
+            .with_tokio_handle(rt.handle().clone());
+        let t_1 = t.clone();
+
+        rt.block_on(async move {
+            drop(t);
+            drop(t_1);
+
+            while inner.high_water.load(Ordering::SeqCst) == 0 {
+                tokio::task::yield_now().await;
+            }
+            assert_eq!(inner.high_water.load(Ordering::SeqCst), 2);
+        });
+    }
+}
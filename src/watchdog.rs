@@ -0,0 +1,244 @@
+// Copyright (c) 2024 Yuki Kishimoto
+// Distributed under the MIT software license
+
+//! Watchdog destroyer
+
+use alloc::boxed::Box;
+use alloc::sync::Arc;
+use core::ops::{Deref, DerefMut};
+use core::sync::atomic::{AtomicBool, Ordering};
+use core::time::Duration;
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError, Sender};
+use std::thread;
+
+use crate::{AtomicDestroyer, AtomicDestructor, DestroyStats};
+
+/// Wait for the destruction to complete, up to the timeout
+///
+/// Return `false` if the timeout elapsed.
+type Timer = fn(&Receiver<()>, Duration) -> bool;
+
+fn wait(done: &Receiver<()>, timeout: Duration) -> bool {
+    // Disconnected: the destruction panicked, so it's not stuck
+    !matches!(done.recv_timeout(timeout), Err(RecvTimeoutError::Timeout))
+}
+
+/// Destroyer that reports a destruction taking longer than a timeout
+///
+/// A background thread watches [`AtomicDestroyer::on_destroy`] of the inner:
+/// if it doesn't complete within the timeout, the destroyer is marked as [timed out](WatchdogDestroyer::timed_out)
+/// and, with the `tracing` feature, a warning is logged. The destruction can't be cancelled.
+///
+/// Created with [`AtomicDestructor::with_drop_timeout`].
+#[derive(Debug, Clone)]
+pub struct WatchdogDestroyer<T> {
+    inner: T,
+    timeout: Duration,
+    timed_out: Arc<AtomicBool>,
+    timer: Timer,
+}
+
+impl<T> Deref for WatchdogDestroyer<T> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        &self.inner
+    }
+}
+
+impl<T> DerefMut for WatchdogDestroyer<T> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.inner
+    }
+}
+
+impl<T> WatchdogDestroyer<T> {
+    /// Get the timeout
+    pub fn timeout(&self) -> Duration {
+        self.timeout
+    }
+
+    /// Check if the destruction exceeded the timeout
+    pub fn timed_out(&self) -> bool {
+        self.timed_out.load(Ordering::SeqCst)
+    }
+}
+
+impl<T> AtomicDestroyer for WatchdogDestroyer<T>
+where
+    T: AtomicDestroyer,
+{
+    #[cfg(feature = "tracing")]
+    fn name(&self) -> Option<alloc::string::String> {
+        self.inner.name()
+    }
+
+    fn clone_allowed(&self) -> bool {
+        self.inner.clone_allowed()
+    }
+
+    fn on_clone(&self, counter: usize) {
+        self.inner.on_clone(counter);
+    }
+
+    fn on_any_drop(&self, remaining: usize) {
+        self.inner.on_any_drop(remaining);
+    }
+
+    fn abort_on_destroy_panic(&self) -> bool {
+        self.inner.abort_on_destroy_panic()
+    }
+
+    fn on_destroy(&self) {
+        self.watch(|| self.inner.on_destroy());
+    }
+
+    fn on_destroy_with_stats(&self, stats: DestroyStats) {
+        self.watch(|| self.inner.on_destroy_with_stats(stats));
+    }
+
+    fn on_destroy_fallible(
+        &self,
+        stats: DestroyStats,
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        self.watch(|| self.inner.on_destroy_fallible(stats))
+    }
+}
+
+impl<T> WatchdogDestroyer<T>
+where
+    T: AtomicDestroyer,
+{
+    /// Run the destruction while a background thread watches it
+    fn watch<F, R>(&self, destroy: F) -> R
+    where
+        F: FnOnce() -> R,
+    {
+        let (tx, rx): (Sender<()>, Receiver<()>) = mpsc::channel();
+
+        let timer: Timer = self.timer;
+        let timeout: Duration = self.timeout;
+        let timed_out: Arc<AtomicBool> = self.timed_out.clone();
+        #[cfg(feature = "tracing")]
+        let name: Option<alloc::string::String> = self.inner.name();
+        thread::spawn(move || {
+            if !timer(&rx, timeout) {
+                timed_out.store(true, Ordering::SeqCst);
+
+                #[cfg(feature = "tracing")]
+                match name {
+                    Some(name) => tracing::warn!("{} destruction exceeded {:?}", name, timeout),
+                    None => tracing::warn!("Destruction exceeded {:?}", timeout),
+                }
+            }
+        });
+
+        let res: R = destroy();
+
+        // The watchdog may be already gone
+        let _ = tx.send(());
+
+        res
+    }
+}
+
+impl<T> AtomicDestructor<WatchdogDestroyer<T>>
+where
+    T: AtomicDestroyer,
+{
+    /// New wrapper that reports a destruction taking longer than `timeout`
+    ///
+    /// See [`WatchdogDestroyer`].
+    pub fn with_drop_timeout(inner: T, timeout: Duration) -> Self {
+        Self::new(WatchdogDestroyer {
+            inner,
+            timeout,
+            timed_out: Arc::new(AtomicBool::new(false)),
+            timer: wait,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::StealthClone;
+
+    #[derive(Debug, Clone)]
+    struct Internal {
+        release: Arc<AtomicBool>,
+    }
+
+    impl Internal {
+        fn released() -> Self {
+            Self {
+                release: Arc::new(AtomicBool::new(true)),
+            }
+        }
+    }
+
+    impl AtomicDestroyer for Internal {
+        fn on_destroy(&self) {
+            // Stuck until released
+            while !self.release.load(Ordering::SeqCst) {
+                thread::yield_now();
+            }
+        }
+    }
+
+    /// Clock that is always past the deadline
+    fn expired(_done: &Receiver<()>, _timeout: Duration) -> bool {
+        false
+    }
+
+    /// Clock that never reaches the deadline
+    fn never_expires(done: &Receiver<()>, _timeout: Duration) -> bool {
+        let _ = done.recv();
+        true
+    }
+
+    #[test]
+    fn test_drop_timeout_not_exceeded() {
+        let t = AtomicDestructor::with_drop_timeout(Internal::released(), Duration::from_secs(60));
+        let observer = t.stealth_clone();
+        assert_eq!(observer.timeout(), Duration::from_secs(60));
+
+        drop(t);
+        assert!(observer.is_destroyed());
+        assert!(!observer.timed_out());
+    }
+
+    #[test]
+    fn test_drop_completed_before_deadline() {
+        let t = AtomicDestructor::new(WatchdogDestroyer {
+            inner: Internal::released(),
+            timeout: Duration::from_secs(60),
+            timed_out: Arc::new(AtomicBool::new(false)),
+            timer: never_expires,
+        });
+        let observer = t.stealth_clone();
+
+        drop(t);
+        assert!(observer.is_destroyed());
+        assert!(!observer.timed_out());
+    }
+
+    #[test]
+    fn test_drop_timeout_exceeded() {
+        // The destruction is stuck until the watchdog fires
+        let timed_out = Arc::new(AtomicBool::new(false));
+        let t = AtomicDestructor::new(WatchdogDestroyer {
+            inner: Internal {
+                release: timed_out.clone(),
+            },
+            timeout: Duration::from_secs(60),
+            timed_out,
+            timer: expired,
+        });
+        let observer = t.stealth_clone();
+
+        drop(t);
+        assert!(observer.is_destroyed());
+        assert!(observer.timed_out());
+    }
+}
@@ -0,0 +1,75 @@
+// Copyright (c) 2024 Yuki Kishimoto
+// Distributed under the MIT software license
+
+//! Exercise the core API from a `no_std` crate.
+//!
+//! Run with `cargo test --no-default-features --test no_std` to check the library without `std`.
+
+#![no_std]
+
+use core::sync::atomic::{AtomicUsize, Ordering};
+
+use atomic_destructor::{AtomicDestroyer, AtomicDestructor, StealthClone};
+
+static DESTROYED: AtomicUsize = AtomicUsize::new(0);
+
+#[derive(Debug, Clone)]
+struct Internal;
+
+impl AtomicDestroyer for Internal {
+    fn on_destroy(&self) {
+        DESTROYED.fetch_add(1, Ordering::SeqCst);
+    }
+}
+
+#[test]
+fn test_core_api() {
+    let t = AtomicDestructor::new(Internal);
+    let t_1 = t.clone();
+    let stealth = t.stealth_clone();
+    assert_eq!(t.counter(), 2);
+    assert!(stealth.is_stealth());
+
+    drop(t_1);
+    drop(t);
+    assert!(stealth.is_destroyed());
+    assert_eq!(DESTROYED.load(Ordering::SeqCst), 1);
+
+    drop(stealth);
+    assert_eq!(DESTROYED.load(Ordering::SeqCst), 1);
+}
+
+#[test]
+fn test_explicit_teardown() {
+    #[derive(Debug, Clone)]
+    struct Counter(&'static AtomicUsize);
+
+    impl AtomicDestroyer for Counter {
+        fn on_destroy(&self) {
+            self.0.fetch_add(1, Ordering::SeqCst);
+        }
+    }
+
+    static COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+    // Destroy now
+    let t = AtomicDestructor::new(Counter(&COUNTER));
+    let observer = t.stealth_clone();
+    assert!(observer.peek().is_some());
+    assert!(t.try_destroy());
+    assert!(!t.try_destroy());
+    assert!(observer.peek().is_none());
+    assert!(observer.try_to_owned_inner().is_none());
+    drop(t);
+    assert_eq!(COUNTER.load(Ordering::SeqCst), 1);
+
+    // Destroy and get back the inner
+    let t = AtomicDestructor::new(Counter(&COUNTER));
+    assert!(t.destroy_and_into_inner().is_ok());
+    assert_eq!(COUNTER.load(Ordering::SeqCst), 2);
+
+    // Reclaim the inner, without destroying it
+    let t = AtomicDestructor::new(Counter(&COUNTER));
+    assert!(t.transfer_inner().is_some());
+    assert_eq!(COUNTER.load(Ordering::SeqCst), 2);
+}